@@ -80,6 +80,9 @@
 
  Traits can mandate methods on a type and associated functions as well
  */
+mod ranking;
+mod stays;
+
 use std::collections::HashMap;
 use std::fmt::Display;
 
@@ -92,8 +95,14 @@ trait Accommodation {
 }
 
 trait Rating {
-    fn get_rating(&self) -> u32 {
-        return 0;
+    fn get_reviews(&self) -> &[u32]; // Star ratings left by guests, from ranking::MIN_STARS to ranking::MAX_STARS
+    fn add_review(&mut self, stars: u32) -> Result<(), String>; // The only way in, so every stored review has been through ranking::check_stars
+
+    fn get_rating(&self) -> u32 { // The average rounded to the nearest star, or 0 until someone leaves a review
+        match ranking::average_score(self) {
+            Some(average) => average.round() as u32,
+            None => 0,
+        }
     }
 }
 
@@ -101,6 +110,7 @@ trait Rating {
 struct Hotel {
     name: String,
     reservations: HashMap<String, u32>,
+    reviews: Vec<u32>,
 }
 
 impl Hotel {
@@ -108,6 +118,7 @@ impl Hotel {
         Self {
             name: name.to_string(),
             reservations: HashMap::new(),
+            reviews: Vec::new(),
         }
     }
 
@@ -127,8 +138,13 @@ impl Accommodation for Hotel {
 }
 
 impl Rating for Hotel {
-    fn get_rating(&self) -> u32 {
-        return 5;
+    fn get_reviews(&self) -> &[u32] {
+        &self.reviews
+    }
+
+    fn add_review(&mut self, stars: u32) -> Result<(), String> {
+        self.reviews.push(ranking::check_stars(stars)?);
+        Ok(())
     }
 }
 
@@ -137,6 +153,7 @@ impl Rating for Hotel {
 struct Hotel_2<T> {
     name: T,
     reservations: HashMap<String, u32>,
+    reviews: Vec<u32>,
 }
 
 impl<T> Hotel_2<T> { // This form of declaration means that everything defined in this scope works for any generic input of type T. We use the trait bound Display to ensure that T has a display method. This is so there are no conflicts with the Display trait when we try printing! The problem here is that we limit our constructor to the display trait as well. We can easily fix this by using 2 different impl blocks, one for the generic type T and one for the trait bound Display.
@@ -144,6 +161,7 @@ impl<T> Hotel_2<T> { // This form of declaration means that everything defined i
         Self {
             name: name,
             reservations: HashMap::new(),
+            reviews: Vec::new(),
         }
     }
 }
@@ -167,15 +185,21 @@ impl<T: Display> Accommodation for Hotel_2<T> {
 
 
 impl<T> Rating for Hotel_2<T> {
-    fn get_rating(&self) -> u32 {
-        return 5;
+    fn get_reviews(&self) -> &[u32] {
+        &self.reviews
+    }
+
+    fn add_review(&mut self, stars: u32) -> Result<(), String> {
+        self.reviews.push(ranking::check_stars(stars)?);
+        Ok(())
     }
 }
 
 # [derive(Debug)]
 struct AirBnB {
     host: String,
-    guests: Vec<(String, u32)> // String is the guest name and u32 is the number of nights they are staying
+    guests: Vec<(String, u32)>, // String is the guest name and u32 is the number of nights they are staying
+    reviews: Vec<u32>,
 }
 
 impl AirBnB {
@@ -183,6 +207,7 @@ impl AirBnB {
         Self {
             host: host.to_string(),
             guests: Vec::<(String, u32)>::new(),
+            reviews: Vec::new(),
         }
     }
 }
@@ -198,8 +223,13 @@ impl Accommodation for AirBnB {
 }
 
 impl Rating for AirBnB {
-    fn get_rating(&self) -> u32 {
-        return 3;
+    fn get_reviews(&self) -> &[u32] {
+        &self.reviews
+    }
+
+    fn add_review(&mut self, stars: u32) -> Result<(), String> {
+        self.reviews.push(ranking::check_stars(stars)?);
+        Ok(())
     }
 }
 
@@ -235,12 +265,23 @@ where // We are using the "where" clause here!
     second.book(guest, 1);
 }
 
-fn choose_best_place_to_stay() -> impl Accommodation {
-    return Hotel::new("The Marriott");
+// Picks the top ranked option out of the collection (see ranking.rs), handing ownership of it back to the caller
+fn choose_best_place_to_stay<T: Accommodation + Rating>(mut places: Vec<T>) -> Option<impl Accommodation> {
+    let best = ranking::best_index(&places)?;
+    Some(places.swap_remove(best))
 }
 
-fn choose_best_place_to_stay2() -> impl Accommodation + Rating { // Implements both traits
-    return Hotel::new("The Marriott");
+fn choose_best_place_to_stay2<T: Accommodation + Rating>(mut places: Vec<T>) -> Option<impl Accommodation + Rating> { // Implements both traits
+    let best = ranking::best_index(&places)?;
+    Some(places.swap_remove(best))
+}
+
+fn rated_hotel(name: &str, reviews: &[u32]) -> Hotel {
+    let mut hotel = Hotel::new(name);
+    for stars in reviews {
+        hotel.add_review(*stars).unwrap();
+    }
+    hotel
 }
 
 fn main() {
@@ -280,8 +321,11 @@ fn main() {
     println!("Multiple Trait Bounds");
 
     println!("Traits as Function Return Values");
-    let mut result = choose_best_place_to_stay(); // Note that the result is something that implements the Accommodation trait, not a concrete type. This causes issues with the compiler. The solution is to have "choose_best_place_to_stay" return something that implements both traits, so that the compiler knows it implements both traits and can call methods from both traits on it!
-    let mut result2 = choose_best_place_to_stay2();
+    let candidates = vec![rated_hotel("The Marriott", &[5, 4, 5, 4, 5, 5, 4]), rated_hotel("Brand New Inn", &[5])];
+    let mut result = choose_best_place_to_stay(candidates).unwrap(); // Note that the result is something that implements the Accommodation trait, not a concrete type. This causes issues with the compiler. The solution is to have "choose_best_place_to_stay" return something that implements both traits, so that the compiler knows it implements both traits and can call methods from both traits on it!
+    let candidates = vec![rated_hotel("The Marriott", &[5, 4, 5, 4, 5, 5, 4]), rated_hotel("Brand New Inn", &[5])];
+    let mut result2 = choose_best_place_to_stay2(candidates).unwrap();
+    println!("Best place to stay: {} ({} stars)", result2.get_description(), result2.get_rating());
     // mix_and_max(&mut result, &mut air_bnb, "Sindbad"); // The compiler knows where giving it a type that implement Accommodation, but it doesn't know if it implements Rating as well!
     mix_and_max(&mut result2, &mut air_bnb, "Sindbad");
 
@@ -303,4 +347,48 @@ fn main() {
 
     println!("Trait Must be In Scope to Use it's Definitions");
 
+    println!("Ranking by Rating");
+    let hotels = vec![
+        rated_hotel("Brand New Inn", &[5]), // A single perfect review shouldn't beat a long track record
+        rated_hotel("The Marriott", &[5, 4, 5, 4, 5, 5, 4, 5, 5, 4]),
+        rated_hotel("Roadside Motel", &[2, 3, 2, 3, 1]),
+        rated_hotel("Unreviewed Lodge", &[]),
+    ];
+    for (position, entry) in ranking::rank(&hotels).iter().enumerate() {
+        println!(
+            "{}. {} --> weighted {:.2}, raw {:?}, {} reviews, {} stars",
+            position + 1,
+            entry.place.name,
+            entry.score,
+            ranking::average_score(entry.place),
+            entry.review_count,
+            entry.place.get_rating()
+        );
+    }
+    if let Err(error) = air_bnb.add_review(7) {
+        println!("Review rejected: {}", error);
+    }
+
+    println!("Trait Objects for Mixed Accommodations");
+    let mut mixed: Vec<Box<dyn stays::Stay>> = vec![Box::new(Hotel::new("The Luxe")), Box::new(AirBnB::new("Peter"))];
+    stays::book_all(&mut mixed, "Yasmin", 2);
+    book_for_five_nights(&mut mixed[1], "Omar"); // The Box forwards to the AirBnB inside, so the generic functions accept it too
+    for summary in stays::summarize_all(&mixed) {
+        println!("{}", summary);
+    }
+
+    let config = concat!(env!("CARGO_MANIFEST_DIR"), "/stays.cfg");
+    match stays::load_stays(config) {
+        Ok(mut loaded) => {
+            stays::book_all(&mut loaded, "Layla", 3);
+            for entry in ranking::rank(&loaded) {
+                println!("{:.2} --> {}", entry.score, stays::summarize(entry.place.as_ref()));
+            }
+        }
+        Err(error) => println!("{}", error),
+    }
+    if let Err(error) = stays::parse_stays("castle | Dracula's |") {
+        println!("{}", error);
+    }
+
 }
@@ -0,0 +1,75 @@
+/*
+ * Ranking Accommodations by Rating
+ * A plain average over-ranks new listings: one 5 star review beats a hundred 4.9 star reviews. A Bayesian average pretends every
+ * listing already has PRIOR_WEIGHT reviews at the average of the whole collection, so a listing only rises above the pack once it
+ * has enough real reviews to outweigh that prior.
+ *
+ * score = (PRIOR_WEIGHT * prior_mean + sum of stars) / (PRIOR_WEIGHT + number of reviews)
+ */
+use crate::{Accommodation, Rating};
+
+pub const MIN_STARS: u32 = 1;
+pub const MAX_STARS: u32 = 5;
+pub const PRIOR_WEIGHT: f64 = 5.0;
+
+// Every Rating's add_review runs stars through this before storing them
+pub fn check_stars(stars: u32) -> Result<u32, String> {
+    if !(MIN_STARS..=MAX_STARS).contains(&stars) {
+        return Err(format!("{} stars is outside {}-{}", stars, MIN_STARS, MAX_STARS));
+    }
+    Ok(stars)
+}
+
+pub fn average_score<R: Rating + ?Sized>(place: &R) -> Option<f64> {
+    let reviews = place.get_reviews();
+    if reviews.is_empty() {
+        return None;
+    }
+    Some(reviews.iter().sum::<u32>() as f64 / reviews.len() as f64)
+}
+
+// The average over every review in the collection. With no reviews at all we fall back to the middle of the scale.
+pub fn prior_mean<R: Rating>(places: &[R]) -> f64 {
+    let (total, count) = places
+        .iter()
+        .flat_map(|place| place.get_reviews())
+        .fold((0u32, 0usize), |(total, count), stars| (total + stars, count + 1));
+    if count == 0 {
+        return (MIN_STARS + MAX_STARS) as f64 / 2.0;
+    }
+    total as f64 / count as f64
+}
+
+pub fn bayesian_score<R: Rating + ?Sized>(place: &R, prior_mean: f64, prior_weight: f64) -> f64 {
+    let reviews = place.get_reviews();
+    let total = reviews.iter().sum::<u32>() as f64;
+    (prior_weight * prior_mean + total) / (prior_weight + reviews.len() as f64)
+}
+
+#[derive(Debug)]
+pub struct Ranked<'a, T> {
+    pub place: &'a T,
+    pub score: f64,
+    pub review_count: usize,
+}
+
+// Best first. Ties on score go to the listing with more reviews, then keep the collection's original order.
+pub fn rank<T: Accommodation + Rating>(places: &[T]) -> Vec<Ranked<'_, T>> {
+    let mean = prior_mean(places);
+    let mut ranked: Vec<Ranked<'_, T>> = places
+        .iter()
+        .map(|place| Ranked {
+            place,
+            score: bayesian_score(place, mean, PRIOR_WEIGHT),
+            review_count: place.get_reviews().len(),
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.review_count.cmp(&a.review_count)));
+    ranked
+}
+
+// The position of the top ranked listing, handy when the caller wants to take ownership of it
+pub fn best_index<T: Accommodation + Rating>(places: &[T]) -> Option<usize> {
+    let best = rank(places).into_iter().next()?;
+    places.iter().position(|place| std::ptr::eq(place, best.place))
+}
@@ -0,0 +1,130 @@
+/*
+ * Trait Objects for Mixed Accommodations
+ * A Vec can only hold one type, so to keep hotels and AirBnBs side by side we store Box<dyn Stay> instead. Every method call on
+ * the box is looked up in its vtable at runtime (dynamic dispatch), which is what lets the concrete type be decided from a config file.
+ *
+ * A trait object can only name one non-auto trait, so Stay bundles Accommodation and Rating together. The blanket impl below means
+ * every type that implements both traits is automatically a Stay.
+ */
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use crate::{AirBnB, Accommodation, Hotel, Hotel_2, Rating};
+
+pub trait Stay: Accommodation + Rating {}
+
+impl<T: Accommodation + Rating + ?Sized> Stay for T {}
+
+// Forwarding impls so a Box<dyn Stay> can be passed anywhere an Accommodation or Rating is expected (e.g. book_for_five_nights or ranking::rank)
+impl<T: Accommodation + ?Sized> Accommodation for Box<T> {
+    fn get_description(&self) -> String {
+        (**self).get_description()
+    }
+
+    fn book(&mut self, name: &str, nights: u32) {
+        (**self).book(name, nights)
+    }
+
+    fn get_description2(&self) -> String {
+        (**self).get_description2()
+    }
+}
+
+impl<T: Rating + ?Sized> Rating for Box<T> {
+    fn get_reviews(&self) -> &[u32] {
+        (**self).get_reviews()
+    }
+
+    fn add_review(&mut self, stars: u32) -> Result<(), String> {
+        (**self).add_review(stars)
+    }
+}
+
+pub fn book_all(stays: &mut [Box<dyn Stay>], guest: &str, nights: u32) {
+    for stay in stays.iter_mut() {
+        stay.book(guest, nights);
+    }
+}
+
+pub fn summarize(stay: &dyn Stay) -> String {
+    let reviews = stay.get_reviews().len();
+    if reviews == 0 {
+        return format!("{} (no reviews yet)", stay.get_description());
+    }
+    format!("{} ({} stars from {} reviews)", stay.get_description(), stay.get_rating(), reviews)
+}
+
+pub fn summarize_all(stays: &[Box<dyn Stay>]) -> Vec<String> {
+    stays.iter().map(|stay| summarize(stay.as_ref())).collect()
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "Could not read stays config: {}", error),
+            LoadError::Parse { line, message } => write!(f, "Stays config line {}: {}", line, message),
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(error: std::io::Error) -> Self {
+        LoadError::Io(error)
+    }
+}
+
+// The kind column of the config decides which concrete type gets boxed. Supporting a new type only needs a new arm here.
+fn build(kind: &str, name: &str) -> Option<Box<dyn Stay>> {
+    match kind {
+        "hotel" => Some(Box::new(Hotel::new(name))),
+        "hotel2" => Some(Box::new(Hotel_2::new(name.to_string()))),
+        "airbnb" => Some(Box::new(AirBnB::new(name))),
+        _ => None,
+    }
+}
+
+/*
+ * Config format, one stay per line with "|" separated columns. Blank lines and lines starting with "#" are ignored.
+ *   kind | name | optional space separated star reviews
+ *   hotel | The Luxe | 5 4 5
+ *   airbnb | Peter |
+ */
+pub fn parse_stays(text: &str) -> Result<Vec<Box<dyn Stay>>, LoadError> {
+    let mut stays = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parse_error = |message: String| LoadError::Parse { line: index + 1, message };
+
+        let columns: Vec<&str> = line.split('|').map(str::trim).collect();
+        if columns.len() < 2 || columns.len() > 3 {
+            return Err(parse_error(format!("expected 2 or 3 columns, found {}", columns.len())));
+        }
+        let (kind, name) = (columns[0].to_lowercase(), columns[1]);
+        if name.is_empty() {
+            return Err(parse_error(String::from("the name column is empty")));
+        }
+        let mut stay = build(&kind, name).ok_or_else(|| parse_error(format!("unknown kind '{}'", kind)))?;
+
+        for review in columns.get(2).unwrap_or(&"").split_whitespace() {
+            let stars = review.parse().map_err(|_| parse_error(format!("'{}' is not a star rating", review)))?;
+            stay.add_review(stars).map_err(parse_error)?;
+        }
+        stays.push(stay);
+    }
+    Ok(stays)
+}
+
+pub fn load_stays<P: AsRef<Path>>(path: P) -> Result<Vec<Box<dyn Stay>>, LoadError> {
+    let text = fs::read_to_string(path)?;
+    parse_stays(&text)
+}
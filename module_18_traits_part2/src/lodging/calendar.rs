@@ -0,0 +1,152 @@
+// Calendar dates for reservations and blocked periods. Only whole days matter for lodging, so there is no time of day here.
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::{Accommodation, Reservation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+impl Date {
+    // Returns None for impossible dates such as February 30th
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    // Days since 1970-01-01. This is the "days from civil" algorithm, it shifts the year to start in March so leap days fall at the end.
+    pub fn to_days(&self) -> i64 {
+        let year = if self.month <= 2 { self.year as i64 - 1 } else { self.year as i64 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+
+    // The inverse of to_days
+    pub fn from_days(days: i64) -> Self {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        Self { year: year as i32, month, day }
+    }
+
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    pub fn days_until(&self, other: &Date) -> i64 {
+        other.to_days() - self.to_days()
+    }
+
+    // Parses the compact "YYYYMMDD" form used by iCalendar
+    pub fn parse_basic(text: &str) -> Option<Self> {
+        if text.len() != 8 || !text.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let year = text[0..4].parse().ok()?;
+        let month = text[4..6].parse().ok()?;
+        let day = text[6..8].parse().ok()?;
+        Self::new(year, month, day)
+    }
+
+    pub fn to_basic(&self) -> String {
+        format!("{:04}{:02}{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// A run of nights. `end` is the check-out day, so it is not itself occupied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DateRange {
+    pub start: Date,
+    pub end: Date,
+}
+
+impl DateRange {
+    pub fn new(start: Date, end: Date) -> Option<Self> {
+        if end <= start {
+            return None;
+        }
+        Some(Self { start, end })
+    }
+
+    pub fn from_nights(start: Date, nights: u32) -> Self {
+        Self { start, end: start.add_days(nights as i64) }
+    }
+
+    pub fn nights(&self) -> u32 {
+        self.start.days_until(&self.end) as u32
+    }
+
+    pub fn overlaps(&self, other: &DateRange) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+impl Display for DateRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} to {}", self.start, self.end)
+    }
+}
+
+// Anything with a calendar of its own. Blocked dates come from the host or another platform and can't be booked.
+pub trait Calendar: Accommodation {
+    fn blocked_dates(&self) -> &[DateRange];
+    fn block_dates(&mut self, range: DateRange);
+
+    // Never for zero nights, which would be an empty range that overlaps nothing and exports as an event ending where it starts
+    fn is_available(&self, check_in: Date, nights: u32) -> bool {
+        if nights == 0 {
+            return false;
+        }
+        let wanted = DateRange::from_nights(check_in, nights);
+        let reserved = self.reservations().iter().map(Reservation::dates);
+        let blocked = self.blocked_dates().iter().copied();
+        !reserved.chain(blocked).any(|range| range.overlaps(&wanted))
+    }
+}
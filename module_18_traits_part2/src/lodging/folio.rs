@@ -0,0 +1,399 @@
+// A folio is the running bill for one reservation. Room nights are posted when it's opened, extras are added during the stay,
+// and closing it at check-out applies the taxes and produces the final Invoice. All amounts are whole cents to avoid f64 rounding,
+// and any amount too large for a u64 is an error rather than a wrapped total.
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::calendar::Date;
+use super::guest::GuestId;
+use super::{Accommodation, Reservation};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FolioError {
+    Overflow, // A charge, tax or total doesn't fit in a u64 of cents
+    TooManyRedeemedNights { redeemed: u32, nights: u32 },
+}
+
+impl Display for FolioError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            FolioError::Overflow => write!(f, "The amount is too large to bill"),
+            FolioError::TooManyRedeemedNights { redeemed, nights } => {
+                write!(f, "Cannot redeem {} nights on a {} night stay", redeemed, nights)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FolioError {}
+
+fn checked_total(amounts: impl IntoIterator<Item = Result<u64, FolioError>>) -> Result<u64, FolioError> {
+    amounts.into_iter().try_fold(0u64, |total, amount| total.checked_add(amount?).ok_or(FolioError::Overflow))
+}
+
+pub fn format_cents(cents: u64) -> String {
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Extra {
+    Minibar,
+    Parking,
+    RoomService,
+    Other(String),
+}
+
+impl Display for Extra {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Extra::Minibar => write!(f, "Minibar"),
+            Extra::Parking => write!(f, "Parking"),
+            Extra::RoomService => write!(f, "Room service"),
+            Extra::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChargeKind {
+    RoomNight(Date),
+    RedeemedNight(Date), // Paid for with loyalty points, so it's billed at zero
+    Extra(Extra),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Charge {
+    pub kind: ChargeKind,
+    pub quantity: u32,
+    pub unit_cents: u64,
+}
+
+impl Charge {
+    pub fn amount_cents(&self) -> Result<u64, FolioError> {
+        self.unit_cents.checked_mul(self.quantity as u64).ok_or(FolioError::Overflow)
+    }
+
+    pub fn description(&self) -> String {
+        match &self.kind {
+            ChargeKind::RoomNight(date) => format!("Room night {}", date),
+            ChargeKind::RedeemedNight(date) => format!("Room night {} (points)", date),
+            ChargeKind::Extra(extra) => extra.to_string(),
+        }
+    }
+
+    fn category(&self) -> &'static str {
+        match self.kind {
+            ChargeKind::RoomNight(_) | ChargeKind::RedeemedNight(_) => "room",
+            ChargeKind::Extra(_) => "extra",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaxBase {
+    Room,
+    Extras,
+    Everything,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tax {
+    pub name: String,
+    pub basis_points: u32, // Hundredths of a percent, so 1200 is 12%
+    pub base: TaxBase,
+}
+
+impl Tax {
+    pub fn new(name: &str, basis_points: u32, base: TaxBase) -> Self {
+        Self { name: name.to_string(), basis_points, base }
+    }
+
+    fn applies_to(&self, charge: &Charge) -> bool {
+        matches!(
+            (self.base, &charge.kind),
+            (TaxBase::Everything, _)
+                | (TaxBase::Room, ChargeKind::RoomNight(_) | ChargeKind::RedeemedNight(_))
+                | (TaxBase::Extras, ChargeKind::Extra(_))
+        )
+    }
+
+    // Rounds half a cent up
+    fn amount_on(&self, taxable_cents: u64) -> Result<u64, FolioError> {
+        let scaled = taxable_cents.checked_mul(self.basis_points as u64).and_then(|scaled| scaled.checked_add(5_000));
+        Ok(scaled.ok_or(FolioError::Overflow)? / 10_000)
+    }
+}
+
+pub fn standard_taxes() -> Vec<Tax> {
+    vec![Tax::new("Occupancy tax", 1200, TaxBase::Room), Tax::new("Sales tax", 800, TaxBase::Extras)]
+}
+
+#[derive(Debug, Clone)]
+pub struct Folio {
+    property: String,
+    guest: GuestId,
+    check_in: Date,
+    nights: u32,
+    charges: Vec<Charge>,
+    taxes: Vec<Tax>,
+}
+
+impl Folio {
+    // Posts one room night per night of the reservation at the listing's nightly rate. The first `redeemed_nights` were
+    // paid for with loyalty points (Stay::redeemed_nights) and are posted at zero.
+    pub fn open<A: Accommodation>(place: &A, reservation: &Reservation, redeemed_nights: u32) -> Result<Self, FolioError> {
+        if redeemed_nights > reservation.nights {
+            return Err(FolioError::TooManyRedeemedNights { redeemed: redeemed_nights, nights: reservation.nights });
+        }
+        let rate = place.nightly_rate();
+        let charges = (0..reservation.nights)
+            .map(|night| {
+                let date = reservation.check_in.add_days(night as i64);
+                if night < redeemed_nights {
+                    Charge { kind: ChargeKind::RedeemedNight(date), quantity: 1, unit_cents: 0 }
+                } else {
+                    Charge { kind: ChargeKind::RoomNight(date), quantity: 1, unit_cents: rate }
+                }
+            })
+            .collect();
+        Ok(Self {
+            property: place.name(),
+            guest: reservation.guest,
+            check_in: reservation.check_in,
+            nights: reservation.nights,
+            charges,
+            taxes: standard_taxes(),
+        })
+    }
+
+    pub fn set_taxes(&mut self, taxes: Vec<Tax>) {
+        self.taxes = taxes;
+    }
+
+    // Refused if the charge or the new balance would overflow, so every charge on a folio has an amount
+    pub fn add_extra(&mut self, extra: Extra, quantity: u32, unit_cents: u64) -> Result<(), FolioError> {
+        let charge = Charge { kind: ChargeKind::Extra(extra), quantity, unit_cents };
+        self.balance_cents()?.checked_add(charge.amount_cents()?).ok_or(FolioError::Overflow)?;
+        self.charges.push(charge);
+        Ok(())
+    }
+
+    pub fn charges(&self) -> &[Charge] {
+        &self.charges
+    }
+
+    pub fn balance_cents(&self) -> Result<u64, FolioError> {
+        checked_total(self.charges.iter().map(Charge::amount_cents))
+    }
+
+    // Check-out: taxes are computed on the final charges and the folio becomes an immutable Invoice
+    pub fn close(self) -> Result<Invoice, FolioError> {
+        let subtotal_cents = self.balance_cents()?;
+        let taxes = self
+            .taxes
+            .iter()
+            .map(|tax| {
+                let taxable = checked_total(self.charges.iter().filter(|charge| tax.applies_to(charge)).map(Charge::amount_cents))?;
+                Ok(TaxLine { name: tax.name.clone(), basis_points: tax.basis_points, amount_cents: tax.amount_on(taxable)? })
+            })
+            .collect::<Result<Vec<_>, FolioError>>()?;
+        let total_cents = checked_total(std::iter::once(Ok(subtotal_cents)).chain(taxes.iter().map(|tax| Ok(tax.amount_cents))))?;
+        Ok(Invoice {
+            property: self.property,
+            guest: self.guest,
+            check_in: self.check_in,
+            check_out: self.check_in.add_days(self.nights as i64),
+            charges: self.charges,
+            subtotal_cents,
+            taxes,
+            total_cents,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxLine {
+    pub name: String,
+    pub basis_points: u32,
+    pub amount_cents: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invoice {
+    pub property: String,
+    pub guest: GuestId,
+    pub check_in: Date,
+    pub check_out: Date,
+    pub charges: Vec<Charge>,
+    pub subtotal_cents: u64,
+    pub taxes: Vec<TaxLine>,
+    pub total_cents: u64,
+}
+
+fn json_string(text: &str) -> String {
+    let mut output = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+impl Invoice {
+    pub fn to_text(&self) -> String {
+        let mut output = format!(
+            "INVOICE - {}\nGuest: {}\nStay: {} to {}\n\n",
+            self.property, self.guest, self.check_in, self.check_out
+        );
+        for charge in &self.charges {
+            output.push_str(&format!(
+                "{:<30} {:>3} x {:>10} {:>12}\n",
+                charge.description(),
+                charge.quantity,
+                format_cents(charge.unit_cents),
+                charge.amount_cents().map_or_else(|error| error.to_string(), format_cents) // close() checked these
+            ));
+        }
+        output.push_str(&format!("{:<47} {:>12}\n", "Subtotal", format_cents(self.subtotal_cents)));
+        for tax in &self.taxes {
+            let label = format!("{} ({}.{:02}%)", tax.name, tax.basis_points / 100, tax.basis_points % 100);
+            output.push_str(&format!("{:<47} {:>12}\n", label, format_cents(tax.amount_cents)));
+        }
+        output.push_str(&format!("{:<47} {:>12}\n", "TOTAL", format_cents(self.total_cents)));
+        output
+    }
+
+    pub fn to_json(&self) -> String {
+        let charges = self
+            .charges
+            .iter()
+            .map(|charge| {
+                format!(
+                    "{{\"description\":{},\"category\":\"{}\",\"quantity\":{},\"unit_cents\":{},\"amount_cents\":{}}}",
+                    json_string(&charge.description()),
+                    charge.category(),
+                    charge.quantity,
+                    charge.unit_cents,
+                    charge.amount_cents().map_or_else(|_| String::from("null"), |cents| cents.to_string())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let taxes = self
+            .taxes
+            .iter()
+            .map(|tax| {
+                format!(
+                    "{{\"name\":{},\"basis_points\":{},\"amount_cents\":{}}}",
+                    json_string(&tax.name),
+                    tax.basis_points,
+                    tax.amount_cents
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"property\":{},\"guest\":\"{}\",\"check_in\":\"{}\",\"check_out\":\"{}\",\"charges\":[{}],\"subtotal_cents\":{},\"taxes\":[{}],\"total_cents\":{}}}",
+            json_string(&self.property),
+            self.guest,
+            self.check_in,
+            self.check_out,
+            charges,
+            self.subtotal_cents,
+            taxes,
+            self.total_cents
+        )
+    }
+}
+
+impl Display for Invoice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lodging::Hotel;
+
+    fn hotel_stay(nights: u32, rate: u64) -> (Hotel<&'static str>, Reservation) {
+        let mut hotel = Hotel::new("The Luxe");
+        hotel.set_nightly_rate(rate);
+        let reservation = hotel.book(GuestId::new(1), Date::new(2026, 3, 1).unwrap(), nights).unwrap();
+        (hotel, reservation)
+    }
+
+    #[test]
+    fn totals_add_up() {
+        let (hotel, reservation) = hotel_stay(2, 10_000);
+        let mut folio = Folio::open(&hotel, &reservation, 0).unwrap();
+        folio.add_extra(Extra::Minibar, 3, 450).unwrap();
+        assert_eq!(folio.balance_cents(), Ok(21_350));
+        let invoice = folio.close().unwrap();
+        assert_eq!(invoice.subtotal_cents, 21_350);
+        let taxes: Vec<u64> = invoice.taxes.iter().map(|tax| tax.amount_cents).collect();
+        assert_eq!(taxes, [2_400, 108]); // 12% of the rooms, 8% of the minibar
+        assert_eq!(invoice.total_cents, 23_858);
+        assert_eq!(invoice.check_out, Date::new(2026, 3, 3).unwrap());
+    }
+
+    #[test]
+    fn tax_rounds_half_a_cent_up() {
+        let tax = Tax::new("Tax", 500, TaxBase::Everything);
+        assert_eq!(tax.amount_on(1_009), Ok(50)); // 50.45
+        assert_eq!(tax.amount_on(1_010), Ok(51)); // 50.5
+        assert_eq!(tax.amount_on(0), Ok(0));
+        assert_eq!(tax.amount_on(u64::MAX), Err(FolioError::Overflow));
+    }
+
+    #[test]
+    fn redeemed_nights_are_free() {
+        let (hotel, reservation) = hotel_stay(3, 10_000);
+        let invoice = Folio::open(&hotel, &reservation, 1).unwrap().close().unwrap();
+        assert_eq!(invoice.charges[0].kind, ChargeKind::RedeemedNight(reservation.check_in));
+        assert_eq!(invoice.subtotal_cents, 20_000);
+        assert_eq!(invoice.total_cents, 22_400);
+        assert_eq!(
+            Folio::open(&hotel, &reservation, 4).unwrap_err(),
+            FolioError::TooManyRedeemedNights { redeemed: 4, nights: 3 }
+        );
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let (hotel, reservation) = hotel_stay(2, u64::MAX / 2 + 1);
+        let folio = Folio::open(&hotel, &reservation, 0).unwrap();
+        assert_eq!(folio.balance_cents(), Err(FolioError::Overflow));
+        assert_eq!(folio.close().unwrap_err(), FolioError::Overflow);
+
+        let (hotel, reservation) = hotel_stay(1, 100);
+        let mut folio = Folio::open(&hotel, &reservation, 0).unwrap();
+        assert_eq!(folio.add_extra(Extra::Parking, 2, u64::MAX / 2 + 1), Err(FolioError::Overflow));
+        assert_eq!(folio.add_extra(Extra::Parking, 1, u64::MAX - 50), Err(FolioError::Overflow)); // The balance would overflow
+        assert_eq!(folio.charges().len(), 1);
+
+        let (hotel, reservation) = hotel_stay(1, u64::MAX / 2);
+        let mut folio = Folio::open(&hotel, &reservation, 0).unwrap();
+        folio.set_taxes(vec![Tax::new("Everything", 10_000, TaxBase::Everything)]); // 100% doubles the subtotal
+        assert_eq!(folio.close().unwrap_err(), FolioError::Overflow);
+    }
+
+    #[test]
+    fn json_escapes_text() {
+        let (hotel, reservation) = hotel_stay(1, 100);
+        let mut folio = Folio::open(&hotel, &reservation, 0).unwrap();
+        folio.add_extra(Extra::Other(String::from("Say \"hi\"\\\n\t\u{1}")), 1, 100).unwrap();
+        let json = folio.close().unwrap().to_json();
+        assert!(json.contains(r#""description":"Say \"hi\"\\\n\t\u0001""#), "{}", json);
+        assert!(json.starts_with(r#"{"property":"The Luxe","guest":"G0001","#));
+        assert!(json.ends_with(r#""total_cents":220}"#), "{}", json);
+    }
+}
@@ -0,0 +1,239 @@
+// Guests are registered once and then referenced by their GuestId everywhere else (reservations, booking history, etc.)
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::{Accommodation, ListingId, Unavailable};
+use super::calendar::{Calendar, Date};
+
+pub const POINTS_PER_NIGHT: u32 = 100; // Every night the guest pays for earns this many loyalty points
+pub const POINTS_PER_FREE_NIGHT: u32 = 1000; // The cost in loyalty points of one free night on a future stay
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GuestId(u32);
+
+impl GuestId {
+    pub fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Display for GuestId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "G{:04}", self.0)
+    }
+}
+
+// One entry in a guest's booking history. Paid nights earn points, redeemed nights were paid for with points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stay {
+    pub listing: ListingId,
+    pub property: String, // The listing's name at booking time, for display
+    pub check_in: Date,
+    pub nights: u32,
+    pub redeemed_nights: u32,
+    pub points_earned: u32,
+    pub completed: bool, // Flipped once the guest checks out
+}
+
+impl Stay {
+    pub fn paid_nights(&self) -> u32 {
+        self.nights - self.redeemed_nights
+    }
+}
+
+#[derive(Debug)]
+pub struct Guest {
+    id: GuestId,
+    name: String,
+    email: String,
+    phone: String,
+    loyalty_points: u32,
+    history: Vec<Stay>,
+}
+
+impl Guest {
+    fn new(id: GuestId, name: &str, email: &str, phone: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            email: email.to_string(),
+            phone: phone.to_string(),
+            loyalty_points: 0,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> GuestId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn phone(&self) -> &str {
+        &self.phone
+    }
+
+    pub fn loyalty_points(&self) -> u32 {
+        self.loyalty_points
+    }
+
+    pub fn history(&self) -> &[Stay] {
+        &self.history
+    }
+
+    pub fn completed_stays_at(&self, listing: ListingId) -> usize {
+        self.history.iter().filter(|stay| stay.completed && stay.listing == listing).count()
+    }
+
+    pub fn total_nights(&self) -> u32 {
+        self.history.iter().map(|stay| stay.nights).sum()
+    }
+
+    // How many free nights the guest could redeem right now
+    pub fn redeemable_nights(&self) -> u32 {
+        self.loyalty_points / POINTS_PER_FREE_NIGHT
+    }
+}
+
+impl Display for Guest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} {} <{}> ({} points)", self.id, self.name, self.email, self.loyalty_points)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuestError {
+    UnknownGuest(GuestId),
+    InsufficientPoints { needed: u32, available: u32 },
+    TooManyFreeNights { requested: u32, nights: u32 },
+    NoOpenStay { guest: GuestId, property: String },
+    Unavailable { property: String, check_in: Date, nights: u32 },
+    PointsOverflow { nights: u32 }, // The stay is too long to count its points in a u32
+}
+
+impl Display for GuestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            GuestError::UnknownGuest(id) => write!(f, "No guest is registered with id {}", id),
+            GuestError::InsufficientPoints { needed, available } => {
+                write!(f, "Redemption needs {} points but only {} are available", needed, available)
+            }
+            GuestError::TooManyFreeNights { requested, nights } => {
+                write!(f, "Cannot redeem {} free nights on a {} night stay", requested, nights)
+            }
+            GuestError::NoOpenStay { guest, property } => {
+                write!(f, "Guest {} has no open stay at {}", guest, property)
+            }
+            GuestError::Unavailable { property, check_in, nights } => {
+                write!(f, "{} is not available for {} nights from {}", property, nights, check_in)
+            }
+            GuestError::PointsOverflow { nights } => {
+                write!(f, "A {} night stay earns or costs more loyalty points than can be tracked", nights)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GuestError {}
+
+impl From<Unavailable> for GuestError {
+    fn from(error: Unavailable) -> Self {
+        GuestError::Unavailable { property: error.property, check_in: error.check_in, nights: error.nights }
+    }
+}
+
+// Owns every guest profile and hands out ids. Bookings go through here so the guest's history and points stay in sync with the accommodation.
+#[derive(Debug, Default)]
+pub struct GuestRegistry {
+    guests: HashMap<GuestId, Guest>,
+    next_id: u32,
+}
+
+impl GuestRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, name: &str, email: &str, phone: &str) -> GuestId {
+        self.next_id += 1;
+        let id = GuestId(self.next_id);
+        self.guests.insert(id, Guest::new(id, name, email, phone));
+        id
+    }
+
+    pub fn get(&self, id: GuestId) -> Option<&Guest> {
+        self.guests.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.guests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guests.is_empty()
+    }
+
+    pub fn book<A: Calendar>(&mut self, place: &mut A, guest: GuestId, check_in: Date, nights: u32) -> Result<Stay, GuestError> {
+        self.book_with_points(place, guest, check_in, nights, 0)
+    }
+
+    // Books the stay, paying for `free_nights` of it with loyalty points. Only the nights that are paid for accrue new points.
+    pub fn book_with_points<A: Calendar>(
+        &mut self,
+        place: &mut A,
+        guest: GuestId,
+        check_in: Date,
+        nights: u32,
+        free_nights: u32,
+    ) -> Result<Stay, GuestError> {
+        let profile = self.guests.get_mut(&guest).ok_or(GuestError::UnknownGuest(guest))?;
+
+        if free_nights > nights {
+            return Err(GuestError::TooManyFreeNights { requested: free_nights, nights });
+        }
+        let overflow = GuestError::PointsOverflow { nights };
+        let needed = free_nights.checked_mul(POINTS_PER_FREE_NIGHT).ok_or(overflow.clone())?;
+        let points_earned = (nights - free_nights).checked_mul(POINTS_PER_NIGHT).ok_or(overflow.clone())?;
+        if needed > profile.loyalty_points {
+            return Err(GuestError::InsufficientPoints { needed, available: profile.loyalty_points });
+        }
+        let balance = (profile.loyalty_points - needed).checked_add(points_earned).ok_or(overflow)?;
+        place.book(guest, check_in, nights)?;
+
+        let stay = Stay {
+            listing: place.id(),
+            property: place.name(),
+            check_in,
+            nights,
+            redeemed_nights: free_nights,
+            points_earned,
+            completed: false,
+        };
+        profile.loyalty_points = balance;
+        profile.history.push(stay.clone());
+        Ok(stay)
+    }
+
+    // Marks the guest's oldest open stay at the property as completed, which is what allows them to review it
+    pub fn check_out<A: Accommodation>(&mut self, guest: GuestId, place: &A) -> Result<&Stay, GuestError> {
+        let profile = self.guests.get_mut(&guest).ok_or(GuestError::UnknownGuest(guest))?;
+        let listing = place.id();
+        let stay = profile
+            .history
+            .iter_mut()
+            .find(|stay| !stay.completed && stay.listing == listing)
+            .ok_or_else(|| GuestError::NoOpenStay { guest, property: place.name() })?;
+        stay.completed = true;
+        Ok(stay)
+    }
+}
@@ -0,0 +1,341 @@
+// iCalendar (.ics) sync so hosts can share availability with other platforms.
+// Reservations are exported as all-day VEVENTs, and any busy VEVENT in an imported file becomes a blocked date range.
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::path::Path;
+
+use super::Accommodation;
+use super::calendar::{Calendar, Date, DateRange};
+
+const LINE_LIMIT: usize = 75; // RFC 5545 lines are at most 75 octets, longer ones are folded onto continuation lines
+const PRODUCT_ID: &str = "-//learning_rust//lodging//EN";
+
+#[derive(Debug)]
+pub enum IcalError {
+    MissingCalendar,
+    UnterminatedEvent,
+    MissingStart,
+    InvalidDate(String),
+    InvalidDuration(String),
+    Io(std::io::Error),
+}
+
+impl Display for IcalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            IcalError::MissingCalendar => write!(f, "No VCALENDAR found"),
+            IcalError::UnterminatedEvent => write!(f, "A VEVENT is missing its END:VEVENT line"),
+            IcalError::MissingStart => write!(f, "A VEVENT has no DTSTART"),
+            IcalError::InvalidDate(value) => write!(f, "Could not parse date '{}'", value),
+            IcalError::InvalidDuration(value) => write!(f, "Could not parse duration '{}'", value),
+            IcalError::Io(error) => write!(f, "Could not access calendar file: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for IcalError {}
+
+impl From<std::io::Error> for IcalError {
+    fn from(error: std::io::Error) -> Self {
+        IcalError::Io(error)
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+// Splits a line into 75 octet chunks without cutting a UTF-8 character in half. Continuation lines start with a space.
+fn fold_line(line: &str, output: &mut String) {
+    let mut remaining = line;
+    let mut limit = LINE_LIMIT;
+    while remaining.len() > limit {
+        let mut cut = limit;
+        while !remaining.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        output.push_str(&remaining[..cut]);
+        output.push_str("\r\n ");
+        remaining = &remaining[cut..];
+        limit = LINE_LIMIT - 1; // The leading space counts towards the limit
+    }
+    output.push_str(remaining);
+    output.push_str("\r\n");
+}
+
+fn slug(name: &str) -> String {
+    name.chars()
+        .filter_map(|c| if c.is_ascii_alphanumeric() { Some(c.to_ascii_lowercase()) } else if c == ' ' { Some('-') } else { None })
+        .collect()
+}
+
+pub fn export<A: Accommodation>(place: &A) -> String {
+    let name = place.name();
+    let mut output = String::new();
+    fold_line("BEGIN:VCALENDAR", &mut output);
+    fold_line("VERSION:2.0", &mut output);
+    fold_line(&format!("PRODID:{}", PRODUCT_ID), &mut output);
+    fold_line(&format!("X-WR-CALNAME:{}", escape_text(&name)), &mut output);
+    for reservation in place.reservations() {
+        let dates = reservation.dates();
+        fold_line("BEGIN:VEVENT", &mut output);
+        fold_line(
+            &format!("UID:{}-{}-{}@lodging", slug(&name), reservation.guest, dates.start.to_basic()),
+            &mut output,
+        );
+        fold_line(&format!("DTSTART;VALUE=DATE:{}", dates.start.to_basic()), &mut output);
+        fold_line(&format!("DTEND;VALUE=DATE:{}", dates.end.to_basic()), &mut output);
+        fold_line(
+            &format!("SUMMARY:{}", escape_text(&format!("Reserved at {} by {}", name, reservation.guest))),
+            &mut output,
+        );
+        fold_line("END:VEVENT", &mut output);
+    }
+    fold_line("END:VCALENDAR", &mut output);
+    output
+}
+
+// Joins folded lines back together. Accepts both CRLF and bare LF line endings since not every exporter is strict.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(continuation) = raw.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(continuation);
+            continue;
+        }
+        if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+struct ContentLine {
+    name: String,
+    value: String,
+}
+
+// "DTSTART;VALUE=DATE:20261019" -> name DTSTART, value 20261019. The parameters are dropped since the value's shape already
+// tells dates from date-times, but colons inside quoted parameters (e.g. TZID) must not be mistaken for the separator.
+fn parse_content_line(line: &str) -> ContentLine {
+    let mut in_quotes = false;
+    let mut split = line.len();
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => {
+                split = index;
+                break;
+            }
+            _ => {}
+        }
+    }
+    let (head, value) = (&line[..split], line.get(split + 1..).unwrap_or(""));
+    let name = head.split(';').next().unwrap_or("").to_ascii_uppercase();
+    ContentLine { name, value: value.to_string() }
+}
+
+// Either an all-day "YYYYMMDD" value or a date-time "YYYYMMDDTHHMMSS[Z]". Only the date matters: a timed event ending
+// at 11:00 on the 12th frees up the night of the 12th, exactly like an all-day event whose DTEND is the 12th.
+fn parse_date_value(value: &str) -> Result<Date, IcalError> {
+    let invalid = || IcalError::InvalidDate(value.to_string());
+    let (date, time) = value.split_once('T').unwrap_or((value, "000000"));
+    let time = time.trim_end_matches('Z');
+    if time.len() != 6 || !time.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    Date::parse_basic(date).ok_or_else(invalid)
+}
+
+// Durations only matter to the day, so "PT5H" still blocks a single day
+fn parse_duration_days(value: &str) -> Result<i64, IcalError> {
+    let invalid = || IcalError::InvalidDuration(value.to_string());
+    let body = value.strip_prefix('P').or_else(|| value.strip_prefix("+P")).ok_or_else(invalid)?;
+    let (date_part, time_part) = body.split_once('T').unwrap_or((body, ""));
+    let mut days = 0;
+    let mut number = String::new();
+    for c in date_part.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let amount: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        match c {
+            'W' => days += amount * 7,
+            'D' => days += amount,
+            _ => return Err(invalid()),
+        }
+    }
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+    if time_part.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        days += 1;
+    }
+    Ok(days.max(1))
+}
+
+#[derive(Default)]
+struct PendingEvent {
+    start: Option<Date>,
+    end: Option<Date>,
+    duration: Option<i64>,
+    free: bool, // Cancelled or transparent events don't make the listing busy
+}
+
+impl PendingEvent {
+    fn into_range(self) -> Result<Option<DateRange>, IcalError> {
+        let start = self.start.ok_or(IcalError::MissingStart)?;
+        if self.free {
+            return Ok(None);
+        }
+        let end = match (self.end, self.duration) {
+            (Some(end), _) => end,
+            (None, Some(days)) => start.add_days(days),
+            (None, None) => start.add_days(1),
+        };
+        Ok(Some(DateRange::new(start, end).unwrap_or(DateRange::from_nights(start, 1)))) // Same-day events still block that day
+    }
+}
+
+pub fn parse_blocked_dates(text: &str) -> Result<Vec<DateRange>, IcalError> {
+    let lines = unfold(text);
+    if !lines.iter().any(|line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err(IcalError::MissingCalendar);
+    }
+
+    let mut ranges = Vec::new();
+    let mut event: Option<PendingEvent> = None;
+    for line in &lines {
+        let content = parse_content_line(line);
+        let value = content.value.as_str();
+        match (content.name.as_str(), event.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => event = Some(PendingEvent::default()),
+            ("BEGIN", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => return Err(IcalError::UnterminatedEvent),
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                if let Some(range) = event.take().unwrap_or_default().into_range()? {
+                    ranges.push(range);
+                }
+            }
+            ("DTSTART", Some(pending)) => pending.start = Some(parse_date_value(value)?),
+            ("DTEND", Some(pending)) => pending.end = Some(parse_date_value(value)?),
+            ("DURATION", Some(pending)) => pending.duration = Some(parse_duration_days(value)?),
+            ("STATUS", Some(pending)) if value.eq_ignore_ascii_case("CANCELLED") => pending.free = true,
+            ("TRANSP", Some(pending)) if value.eq_ignore_ascii_case("TRANSPARENT") => pending.free = true,
+            _ => {}
+        }
+    }
+    if event.is_some() {
+        return Err(IcalError::UnterminatedEvent);
+    }
+    Ok(ranges)
+}
+
+// Blocks every busy event from the .ics text on the listing and returns how many ranges were added
+pub fn import<A: Calendar>(place: &mut A, text: &str) -> Result<usize, IcalError> {
+    let ranges = parse_blocked_dates(text)?;
+    for range in &ranges {
+        place.block_dates(*range);
+    }
+    Ok(ranges.len())
+}
+
+pub fn export_to_file<A: Accommodation, P: AsRef<Path>>(place: &A, path: P) -> Result<(), IcalError> {
+    fs::write(path, export(place))?;
+    Ok(())
+}
+
+pub fn import_file<A: Calendar, P: AsRef<Path>>(place: &mut A, path: P) -> Result<usize, IcalError> {
+    let text = fs::read_to_string(path)?;
+    import(place, &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lodging::Hotel;
+    use crate::lodging::guest::GuestId;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    fn calendar(events: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", events)
+    }
+
+    #[test]
+    fn export_round_trips_through_parse() {
+        let mut hotel = Hotel::new(String::from("The Luxe"));
+        hotel.book(GuestId::new(1), date(2026, 10, 1), 5).unwrap();
+        hotel.book(GuestId::new(2), date(2026, 12, 30), 3).unwrap(); // Runs into the next year
+        let reserved: Vec<DateRange> = hotel.reservations().iter().map(|reservation| reservation.dates()).collect();
+        assert_eq!(parse_blocked_dates(&export(&hotel)).unwrap(), reserved);
+    }
+
+    #[test]
+    fn zero_night_stays_are_refused() {
+        let mut hotel = Hotel::new(String::from("The Luxe"));
+        assert!(hotel.book(GuestId::new(1), date(2026, 10, 1), 0).is_err());
+        hotel.book(GuestId::new(2), date(2026, 10, 1), 1).unwrap();
+        assert_eq!(parse_blocked_dates(&export(&hotel)).unwrap(), vec![DateRange::from_nights(date(2026, 10, 1), 1)]);
+    }
+
+    #[test]
+    fn long_names_are_folded_and_still_round_trip() {
+        let name = "The Grand Old Lakeside Lodge and Conference Centre, East Wing, Rooms With A View";
+        let mut hotel = Hotel::new(String::from(name));
+        hotel.book(GuestId::new(1), date(2026, 11, 2), 2).unwrap();
+        let exported = export(&hotel);
+        assert!(exported.split("\r\n").all(|line| line.len() <= LINE_LIMIT));
+        assert!(exported.contains("\r\n "));
+        assert_eq!(parse_blocked_dates(&exported).unwrap(), vec![DateRange::from_nights(date(2026, 11, 2), 2)]);
+    }
+
+    #[test]
+    fn folded_values_are_joined_before_parsing() {
+        let text = calendar("BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:2026\r\n 1201\r\nDTEND;VALUE=DATE:20261204\r\nSUMMARY:Booked\r\n  elsewhere\r\nEND:VEVENT\r\n");
+        assert_eq!(parse_blocked_dates(&text).unwrap(), vec![DateRange::new(date(2026, 12, 1), date(2026, 12, 4)).unwrap()]);
+    }
+
+    #[test]
+    fn all_day_events_block_until_the_end_date() {
+        let text = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261201\r\nDTEND;VALUE=DATE:20261204\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261220\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261224\r\nDURATION:P1W\r\nEND:VEVENT\r\n",
+        );
+        let expected = vec![
+            DateRange::new(date(2026, 12, 1), date(2026, 12, 4)).unwrap(),
+            DateRange::from_nights(date(2026, 12, 20), 1), // No end or duration means just that day
+            DateRange::from_nights(date(2026, 12, 24), 7),
+        ];
+        assert_eq!(parse_blocked_dates(&text).unwrap(), expected);
+    }
+
+    #[test]
+    fn timed_events_only_count_the_dates() {
+        let text = calendar(
+            "BEGIN:VEVENT\r\nDTSTART:20261210T150000Z\r\nDTEND:20261212T110000Z\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART;TZID=\"America/New_York\":20261215T090000\r\nDTEND;TZID=\"America/New_York\":20261215T170000\r\nEND:VEVENT\r\n",
+        );
+        let expected = vec![
+            DateRange::new(date(2026, 12, 10), date(2026, 12, 12)).unwrap(),
+            DateRange::from_nights(date(2026, 12, 15), 1), // Same day events still block that day
+        ];
+        assert_eq!(parse_blocked_dates(&text).unwrap(), expected);
+    }
+
+    #[test]
+    fn imported_ranges_can_not_be_booked() {
+        let mut hotel = Hotel::new(String::from("The Luxe"));
+        let text = calendar("BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261201\r\nDTEND;VALUE=DATE:20261204\r\nEND:VEVENT\r\n");
+        assert_eq!(import(&mut hotel, &text).unwrap(), 1);
+        assert!(hotel.book(GuestId::new(1), date(2026, 12, 3), 2).is_err());
+        assert!(hotel.book(GuestId::new(1), date(2026, 12, 4), 2).is_ok());
+    }
+}
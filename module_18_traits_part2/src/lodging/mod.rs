@@ -1,8 +1,80 @@
-use std::collections::HashMap;
-use std::fmt::Display;
+pub mod calendar;
+pub mod folio;
+pub mod guest;
+pub mod ical;
+pub mod review;
+pub mod storage;
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use calendar::{Calendar, Date, DateRange};
+use guest::GuestId;
+use review::{CheckedReview, Review, Reviewable};
 
 pub trait Accommodation {
-    fn book(&mut self, name: &str, nights: u32); // A mutable reference to an instance
+    fn book(&mut self, guest: GuestId, check_in: Date, nights: u32) -> Result<Reservation, Unavailable>; // A mutable reference to an instance. Refuses nights that are reserved or blocked.
+    fn id(&self) -> ListingId; // What stays and reviews are matched on, since two listings can share a name
+    fn name(&self) -> String; // Used to label the stay in a guest's booking history
+    fn reservations(&self) -> &[Reservation];
+    fn nightly_rate(&self) -> u64; // In cents, this is what a folio charges for each room night
+}
+
+pub const DEFAULT_HOTEL_RATE: u64 = 18900;
+pub const DEFAULT_AIRBNB_RATE: u64 = 9500;
+
+// Handed out once per listing when it's created, so every listing in the process has its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ListingId(u32);
+
+static NEXT_LISTING_ID: AtomicU32 = AtomicU32::new(1);
+
+impl ListingId {
+    fn next() -> Self {
+        Self(NEXT_LISTING_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Display for ListingId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "L{:04}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reservation {
+    pub guest: GuestId,
+    pub check_in: Date,
+    pub nights: u32,
+}
+
+impl Reservation {
+    pub fn dates(&self) -> DateRange {
+        DateRange::from_nights(self.check_in, self.nights)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unavailable {
+    pub property: String,
+    pub check_in: Date,
+    pub nights: u32,
+}
+
+impl Display for Unavailable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} is not available for {} nights from {}", self.property, self.nights, self.check_in)
+    }
+}
+
+impl std::error::Error for Unavailable {}
+
+// The availability check both listings run before they store a reservation
+fn reserve<A: Calendar>(place: &A, guest: GuestId, check_in: Date, nights: u32) -> Result<Reservation, Unavailable> {
+    if !place.is_available(check_in, nights) {
+        return Err(Unavailable { property: place.name(), check_in, nights });
+    }
+    Ok(Reservation { guest, check_in, nights })
 }
 
 pub trait Description {
@@ -14,51 +86,151 @@ pub trait Description {
 
 #[derive(Debug)]
 pub struct Hotel<T> {
+    id: ListingId,
     name: T,
-    reservations: HashMap<String, u32>,
+    nightly_rate: u64,
+    reservations: Vec<Reservation>,
+    blocked: Vec<DateRange>,
+    reviews: Vec<Review>,
 }
 
 impl<T> Hotel<T> {
     pub fn new(name: T) -> Self {
         Self {
+            id: ListingId::next(),
             name: name,
-            reservations: HashMap::new(),
+            nightly_rate: DEFAULT_HOTEL_RATE,
+            reservations: Vec::new(),
+            blocked: Vec::new(),
+            reviews: Vec::new(),
         }
     }
+
+    pub fn set_nightly_rate(&mut self, cents: u64) {
+        self.nightly_rate = cents;
+    }
 }
 
 impl<T: Display> Hotel<T> {
     pub fn summarize(&self) -> String {
-        return format!("{}: {}", self.name, self.get_description())
+        return format!("{}: {} - {}", self.name, self.get_description(), review::rating_summary(self))
     }
 }
 
 impl<T> Description for Hotel<T> {}
 
-impl<T> Accommodation for Hotel<T> {
-    fn book(&mut self, name: &str, nights:u32) {
-        self.reservations.insert(name.to_string(), nights);
+impl<T: Display> Accommodation for Hotel<T> {
+    fn book(&mut self, guest: GuestId, check_in: Date, nights:u32) -> Result<Reservation, Unavailable> {
+        let reservation = reserve(self, guest, check_in, nights)?;
+        self.reservations.push(reservation);
+        Ok(reservation)
+    }
+
+    fn id(&self) -> ListingId {
+        self.id
+    }
+
+    fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn reservations(&self) -> &[Reservation] {
+        &self.reservations
+    }
+
+    fn nightly_rate(&self) -> u64 {
+        self.nightly_rate
+    }
+}
+
+impl<T: Display> Calendar for Hotel<T> {
+    fn blocked_dates(&self) -> &[DateRange] {
+        &self.blocked
+    }
+
+    fn block_dates(&mut self, range: DateRange) {
+        self.blocked.push(range);
+    }
+}
+
+impl<T: Display> Reviewable for Hotel<T> {
+    fn reviews(&self) -> &[Review] {
+        &self.reviews
+    }
+
+    fn add_review(&mut self, review: CheckedReview) {
+        self.reviews.push(review.into_review());
     }
 }
 
 #[derive(Debug)]
 pub struct AirBnB {
+    id: ListingId,
     host: String,
-    guests: Vec<(String, u32)>,
+    nightly_rate: u64,
+    guests: Vec<Reservation>,
+    blocked: Vec<DateRange>,
+    reviews: Vec<Review>,
 }
 
 impl AirBnB {
     pub fn new(host: String) -> Self {
         Self {
+            id: ListingId::next(),
             host: host,
+            nightly_rate: DEFAULT_AIRBNB_RATE,
             guests: Vec::new(),
+            blocked: Vec::new(),
+            reviews: Vec::new(),
         }
     }
+
+    pub fn set_nightly_rate(&mut self, cents: u64) {
+        self.nightly_rate = cents;
+    }
 }
 
 impl Accommodation for AirBnB {
-    fn book(&mut self, name: &str, nights: u32) {
-        self.guests.push((name.to_string(), nights))
+    fn book(&mut self, guest: GuestId, check_in: Date, nights: u32) -> Result<Reservation, Unavailable> {
+        let reservation = reserve(self, guest, check_in, nights)?;
+        self.guests.push(reservation);
+        Ok(reservation)
+    }
+
+    fn id(&self) -> ListingId {
+        self.id
+    }
+
+    fn name(&self) -> String {
+        format!("{}'s apartment", self.host)
+    }
+
+    fn reservations(&self) -> &[Reservation] {
+        &self.guests
+    }
+
+    fn nightly_rate(&self) -> u64 {
+        self.nightly_rate
+    }
+}
+
+impl Calendar for AirBnB {
+    fn blocked_dates(&self) -> &[DateRange] {
+        &self.blocked
+    }
+
+    fn block_dates(&mut self, range: DateRange) {
+        self.blocked.push(range);
+    }
+}
+
+impl Reviewable for AirBnB {
+    fn reviews(&self) -> &[Review] {
+        &self.reviews
+    }
+
+    fn add_review(&mut self, review: CheckedReview) {
+        self.reviews.push(review.into_review());
     }
 }
 
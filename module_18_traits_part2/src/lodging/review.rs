@@ -0,0 +1,109 @@
+// Reviews can only be left by guests who have completed (checked out of) a stay at the listing
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::Accommodation;
+use super::guest::{GuestId, GuestRegistry};
+
+pub const MIN_RATING: u8 = 1;
+pub const MAX_RATING: u8 = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Review {
+    pub guest: GuestId,
+    pub rating: u8,
+    pub text: String,
+}
+
+impl Display for Review {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}/{} from {}: {}", self.rating, MAX_RATING, self.guest, self.text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewError {
+    UnknownGuest(GuestId),
+    RatingOutOfRange(u8),
+    NoCompletedStay { guest: GuestId, property: String },
+    AlreadyReviewed { guest: GuestId, property: String }, // One review per completed stay
+}
+
+impl Display for ReviewError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ReviewError::UnknownGuest(id) => write!(f, "No guest is registered with id {}", id),
+            ReviewError::RatingOutOfRange(rating) => {
+                write!(f, "Rating {} is outside {}-{}", rating, MIN_RATING, MAX_RATING)
+            }
+            ReviewError::NoCompletedStay { guest, property } => {
+                write!(f, "Guest {} has not completed a stay at {}", guest, property)
+            }
+            ReviewError::AlreadyReviewed { guest, property } => {
+                write!(f, "Guest {} has already reviewed every completed stay at {}", guest, property)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReviewError {}
+
+// A review that has passed leave_review's checks. Only this module can make one, so add_review can't be used to skip them.
+#[derive(Debug)]
+pub struct CheckedReview(Review);
+
+impl CheckedReview {
+    pub fn into_review(self) -> Review {
+        self.0
+    }
+}
+
+// A listing that stores reviews. Implementors only provide the storage, the aggregates come for free.
+pub trait Reviewable: Accommodation {
+    fn reviews(&self) -> &[Review];
+    fn add_review(&mut self, review: CheckedReview);
+
+    fn review_count(&self) -> usize {
+        self.reviews().len()
+    }
+
+    fn average_rating(&self) -> Option<f64> {
+        if self.reviews().is_empty() {
+            return None;
+        }
+        let total: u32 = self.reviews().iter().map(|review| review.rating as u32).sum();
+        Some(total as f64 / self.reviews().len() as f64)
+    }
+}
+
+pub fn leave_review<A: Reviewable>(
+    registry: &GuestRegistry,
+    place: &mut A,
+    guest: GuestId,
+    rating: u8,
+    text: &str,
+) -> Result<(), ReviewError> {
+    if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+        return Err(ReviewError::RatingOutOfRange(rating));
+    }
+
+    let profile = registry.get(guest).ok_or(ReviewError::UnknownGuest(guest))?;
+    let completed = profile.completed_stays_at(place.id());
+    if completed == 0 {
+        return Err(ReviewError::NoCompletedStay { guest, property: place.name() });
+    }
+    let already_reviewed = place.reviews().iter().filter(|review| review.guest == guest).count();
+    if already_reviewed >= completed {
+        return Err(ReviewError::AlreadyReviewed { guest, property: place.name() });
+    }
+
+    place.add_review(CheckedReview(Review { guest, rating, text: text.to_string() }));
+    Ok(())
+}
+
+// Renders e.g. "4.5/5 (2 reviews)" or "No reviews yet"
+pub fn rating_summary<A: Reviewable>(place: &A) -> String {
+    match place.average_rating() {
+        Some(average) => format!("{:.1}/{} ({} reviews)", average, MAX_RATING, place.review_count()),
+        None => String::from("No reviews yet"),
+    }
+}
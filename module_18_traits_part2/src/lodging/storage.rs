@@ -0,0 +1,466 @@
+// Storage backends so reservations survive a restart. Listings stay purely in-memory; a store keeps the durable copy and
+// `restore` replays it into a fresh listing on startup. Records are filed under a key the caller picks for each listing:
+// names can be shared and ListingIds are handed out again on every run, so neither one can find a listing's records later.
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::calendar::{Calendar, Date};
+use super::guest::GuestId;
+use super::{Accommodation, Reservation, Unavailable};
+
+const JOURNAL_FILE: &str = "journal.log";
+const SNAPSHOT_FILE: &str = "snapshot.dat";
+const LOCK_FILE: &str = "writer.lock";
+const SNAPSHOT_HEADER: &str = "LODGING-SNAPSHOT v2";
+const SNAPSHOT_EVERY: usize = 100; // Fold the journal into a new snapshot after this many appends
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredReservation {
+    pub listing: String, // The stable key the listing's records are filed under
+    pub property: String, // The listing's name when it was booked, for display
+    pub reservation: Reservation,
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(io::Error),
+    Locked { lock: PathBuf, holder: String }, // Another process already owns the store
+    ConcurrentWrite, // The journal changed underneath us, so appending would interleave with someone else's records
+    Corrupt { file: PathBuf, line: usize, reason: String },
+    Unavailable { property: String, check_in: Date, nights: u32 },
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            StorageError::Io(error) => write!(f, "Storage I/O failed: {}", error),
+            StorageError::Locked { lock, holder } => {
+                write!(f, "Store is locked by {} (remove {} if that process is gone)", holder, lock.display())
+            }
+            StorageError::ConcurrentWrite => write!(f, "The journal was modified by another writer"),
+            StorageError::Corrupt { file, line, reason } => {
+                write!(f, "{} line {} is corrupt: {}", file.display(), line, reason)
+            }
+            StorageError::Unavailable { property, check_in, nights } => {
+                write!(f, "{} is not available for {} nights from {}", property, nights, check_in)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<Unavailable> for StorageError {
+    fn from(error: Unavailable) -> Self {
+        StorageError::Unavailable { property: error.property, check_in: error.check_in, nights: error.nights }
+    }
+}
+
+impl From<io::Error> for StorageError {
+    fn from(error: io::Error) -> Self {
+        StorageError::Io(error)
+    }
+}
+
+pub trait ReservationStore {
+    fn append(&mut self, record: &StoredReservation) -> Result<(), StorageError>;
+    fn load(&self) -> Result<Vec<StoredReservation>, StorageError>;
+
+    // Compacts whatever the backend keeps into its cheapest form. Nothing to do by default.
+    fn snapshot(&mut self) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryStore {
+    records: Vec<StoredReservation>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ReservationStore for InMemoryStore {
+    fn append(&mut self, record: &StoredReservation) -> Result<(), StorageError> {
+        self.records.push(record.clone());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<StoredReservation>, StorageError> {
+        Ok(self.records.clone())
+    }
+}
+
+// Tabs and newlines separate fields and records, so they're escaped inside keys and property names
+fn escape_field(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape_field(text: &str) -> String {
+    let mut output = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => output.push('\t'),
+            Some('n') => output.push('\n'),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+fn encode(record: &StoredReservation) -> String {
+    let reservation = &record.reservation;
+    format!(
+        "{}\t{}\t{}\t{}\t{}",
+        escape_field(&record.listing),
+        escape_field(&record.property),
+        reservation.guest.value(),
+        reservation.check_in.to_basic(),
+        reservation.nights
+    )
+}
+
+fn decode(fields: &[&str]) -> Result<StoredReservation, String> {
+    let [listing, property, guest, check_in, nights] = fields else {
+        return Err(format!("expected 5 fields, found {}", fields.len()));
+    };
+    let guest = guest.parse().map_err(|_| format!("bad guest id '{}'", guest))?;
+    let check_in = Date::parse_basic(check_in).ok_or_else(|| format!("bad date '{}'", check_in))?;
+    let nights = nights.parse().map_err(|_| format!("bad night count '{}'", nights))?;
+    Ok(StoredReservation {
+        listing: unescape_field(listing),
+        property: unescape_field(property),
+        reservation: Reservation { guest: GuestId::new(guest), check_in, nights },
+    })
+}
+
+// Everything in the file up to its last newline. A crash mid-append can leave a torn final record, possibly cut off in the
+// middle of a multibyte character, so that tail is dropped before the rest is decoded.
+fn read_intact(path: &Path) -> Result<(String, u64), StorageError> {
+    let mut bytes = fs::read(path)?;
+    let intact = bytes.iter().rposition(|&byte| byte == b'\n').map_or(0, |index| index + 1);
+    bytes.truncate(intact);
+    let contents = String::from_utf8(bytes).map_err(|error| {
+        let valid = &error.as_bytes()[..error.utf8_error().valid_up_to()];
+        let line = valid.iter().filter(|&&byte| byte == b'\n').count() + 1;
+        StorageError::Corrupt { file: path.to_path_buf(), line, reason: String::from("not valid UTF-8") }
+    })?;
+    Ok((contents, intact as u64))
+}
+
+// An append-only journal plus a periodic snapshot, all inside one directory:
+//   snapshot.dat - a header line "LODGING-SNAPSHOT v2 <sequence>" followed by one record per line
+//   journal.log  - "<sequence>\t<record>" lines appended since (or just before) the last snapshot
+//   writer.lock  - created exclusively on open so a second process can't write at the same time
+// Every journal record gets an increasing sequence number. A snapshot remembers the last sequence it includes, so if we crash
+// after writing the snapshot but before truncating the journal, the already-included records are skipped on the next load.
+#[derive(Debug)]
+pub struct FileStore {
+    directory: PathBuf,
+    journal: File,
+    journal_len: u64, // What we believe the journal's size is. Anything else means a foreign writer.
+    sequence: u64,
+    appends_since_snapshot: usize,
+}
+
+impl FileStore {
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self, StorageError> {
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory)?;
+
+        let lock = directory.join(LOCK_FILE);
+        match OpenOptions::new().write(true).create_new(true).open(&lock) {
+            Ok(mut file) => writeln!(file, "pid {}", std::process::id())?,
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                let holder = fs::read_to_string(&lock).unwrap_or_default().trim().to_string();
+                return Err(StorageError::Locked { lock, holder });
+            }
+            Err(error) => return Err(error.into()),
+        }
+
+        // From here on the lock is ours, so release it if anything below fails
+        let opened = Self::open_locked(directory.clone());
+        if opened.is_err() {
+            let _ = fs::remove_file(&lock);
+        }
+        opened
+    }
+
+    fn open_locked(directory: PathBuf) -> Result<Self, StorageError> {
+        let journal_path = directory.join(JOURNAL_FILE);
+        let journal = OpenOptions::new().create(true).append(true).read(true).open(&journal_path)?;
+
+        // Cut off a torn final line so new records start cleanly
+        let (_, intact) = read_intact(&journal_path)?;
+        if intact < journal.metadata()?.len() {
+            journal.set_len(intact)?;
+        }
+
+        let mut store = Self { directory, journal, journal_len: intact, sequence: 0, appends_since_snapshot: 0 };
+        let (snapshot_sequence, _) = store.read_snapshot()?;
+        let journal_records = store.read_journal(snapshot_sequence)?;
+        store.sequence = journal_records.last().map(|(sequence, _)| *sequence).unwrap_or(snapshot_sequence);
+        store.appends_since_snapshot = journal_records.len();
+        Ok(store)
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn read_snapshot(&self) -> Result<(u64, Vec<StoredReservation>), StorageError> {
+        let path = self.directory.join(SNAPSHOT_FILE);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok((0, Vec::new())),
+            Err(error) => return Err(error.into()),
+        };
+        let corrupt = |line: usize, reason: String| StorageError::Corrupt { file: path.clone(), line, reason };
+
+        let mut lines = contents.lines();
+        let header = lines.next().unwrap_or("");
+        let sequence = header
+            .strip_prefix(SNAPSHOT_HEADER)
+            .and_then(|rest| rest.trim().parse().ok())
+            .ok_or_else(|| corrupt(1, format!("unexpected header '{}'", header)))?;
+        let mut records = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            records.push(decode(&fields).map_err(|reason| corrupt(index + 2, reason))?);
+        }
+        Ok((sequence, records))
+    }
+
+    // Journal records newer than the snapshot, paired with their sequence numbers
+    fn read_journal(&self, after: u64) -> Result<Vec<(u64, StoredReservation)>, StorageError> {
+        let path = self.directory.join(JOURNAL_FILE);
+        let (contents, _) = read_intact(&path)?;
+        let corrupt = |line: usize, reason: String| StorageError::Corrupt { file: path.clone(), line, reason };
+
+        let mut records = Vec::new();
+        let mut previous = after;
+        for (index, line) in contents.lines().enumerate() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let sequence: u64 = fields[0].parse().map_err(|_| corrupt(index + 1, format!("bad sequence '{}'", fields[0])))?;
+            if sequence <= after {
+                continue;
+            }
+            if sequence != previous + 1 {
+                return Err(corrupt(index + 1, format!("sequence {} follows {}", sequence, previous)));
+            }
+            previous = sequence;
+            records.push((sequence, decode(&fields[1..]).map_err(|reason| corrupt(index + 1, reason))?));
+        }
+        Ok(records)
+    }
+
+    fn check_journal_unchanged(&self) -> Result<(), StorageError> {
+        if self.journal.metadata()?.len() != self.journal_len {
+            return Err(StorageError::ConcurrentWrite);
+        }
+        Ok(())
+    }
+}
+
+impl ReservationStore for FileStore {
+    fn append(&mut self, record: &StoredReservation) -> Result<(), StorageError> {
+        self.check_journal_unchanged()?;
+        let line = format!("{}\t{}\n", self.sequence + 1, encode(record));
+        self.journal.write_all(line.as_bytes())?; // One write per record so a record is never split across two writes
+        self.journal.sync_data()?;
+        self.journal_len += line.len() as u64;
+        self.sequence += 1;
+        self.appends_since_snapshot += 1;
+
+        if self.appends_since_snapshot >= SNAPSHOT_EVERY {
+            self.snapshot()?;
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<StoredReservation>, StorageError> {
+        let (sequence, mut records) = self.read_snapshot()?;
+        records.extend(self.read_journal(sequence)?.into_iter().map(|(_, record)| record));
+        Ok(records)
+    }
+
+    // Writes everything to a temporary file, renames it over the old snapshot (an atomic swap), then empties the journal
+    fn snapshot(&mut self) -> Result<(), StorageError> {
+        self.check_journal_unchanged()?;
+        let records = self.load()?;
+
+        let mut contents = format!("{} {}\n", SNAPSHOT_HEADER, self.sequence);
+        for record in &records {
+            contents.push_str(&encode(record));
+            contents.push('\n');
+        }
+        let temporary = self.directory.join(format!("{}.tmp", SNAPSHOT_FILE));
+        let mut file = File::create(&temporary)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temporary, self.directory.join(SNAPSHOT_FILE))?;
+
+        self.journal.set_len(0)?;
+        self.journal_len = 0;
+        self.appends_since_snapshot = 0;
+        Ok(())
+    }
+}
+
+// Releasing the lock when the store goes out of scope lets the next process (or the next run) open it
+impl Drop for FileStore {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(self.directory.join(LOCK_FILE));
+    }
+}
+
+// Writes the reservation to the store first and only then books it in memory, so a booking is never acknowledged without being durable
+pub fn book<A: Calendar, S: ReservationStore>(
+    store: &mut S,
+    place: &mut A,
+    listing: &str,
+    guest: GuestId,
+    check_in: Date,
+    nights: u32,
+) -> Result<Reservation, StorageError> {
+    if !place.is_available(check_in, nights) {
+        return Err(StorageError::Unavailable { property: place.name(), check_in, nights });
+    }
+    let reservation = Reservation { guest, check_in, nights };
+    store.append(&StoredReservation { listing: listing.to_string(), property: place.name(), reservation })?;
+    place.book(guest, check_in, nights)?; // Just checked, so this only fails if the store and listing already disagree
+    Ok(reservation)
+}
+
+// Replays the reservations filed under the listing's key into it. Returns how many were restored.
+pub fn restore<A: Accommodation, S: ReservationStore>(store: &S, place: &mut A, listing: &str) -> Result<usize, StorageError> {
+    let mut restored = 0;
+    for record in store.load()?.into_iter().filter(|record| record.listing == listing) {
+        let reservation = record.reservation;
+        place.book(reservation.guest, reservation.check_in, reservation.nights)?; // Overlapping records mean the store was edited by hand
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lodging::Hotel;
+
+    // A fresh directory per test, removed again when the test is done
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("lodging-store-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn date(day: u32) -> Date {
+        Date::new(2027, 1, day).unwrap()
+    }
+
+    fn append_raw(directory: &Path, bytes: &[u8]) {
+        let mut journal = OpenOptions::new().append(true).open(directory.join(JOURNAL_FILE)).unwrap();
+        journal.write_all(bytes).unwrap();
+    }
+
+    #[test]
+    fn journal_and_snapshot_survive_a_restart() {
+        let directory = TestDir::new("restart");
+        {
+            let mut store = FileStore::open(&directory.0).unwrap();
+            let mut hotel = Hotel::new("Seaside\tInn"); // Tabs are escaped in the files
+            book(&mut store, &mut hotel, "seaside", GuestId::new(1), date(3), 2).unwrap();
+            store.snapshot().unwrap();
+            assert_eq!(fs::metadata(directory.0.join(JOURNAL_FILE)).unwrap().len(), 0);
+            book(&mut store, &mut hotel, "seaside", GuestId::new(2), date(8), 1).unwrap();
+        }
+        let store = FileStore::open(&directory.0).unwrap();
+        let records = store.load().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].property, "Seaside\tInn");
+        let mut restarted = Hotel::new("Seaside\tInn");
+        assert_eq!(restore(&store, &mut restarted, "seaside").unwrap(), 2);
+        assert_eq!(restarted.reservations()[1], Reservation { guest: GuestId::new(2), check_in: date(8), nights: 1 });
+    }
+
+    #[test]
+    fn same_named_listings_keep_their_own_records() {
+        let mut store = InMemoryStore::new();
+        let (mut north, mut south) = (Hotel::new("Seaside Inn"), Hotel::new("Seaside Inn"));
+        book(&mut store, &mut north, "north", GuestId::new(1), date(3), 2).unwrap();
+        book(&mut store, &mut south, "south", GuestId::new(2), date(3), 2).unwrap();
+        let (mut north_again, mut south_again) = (Hotel::new("Seaside Inn"), Hotel::new("Seaside Inn"));
+        assert_eq!(restore(&store, &mut north_again, "north").unwrap(), 1);
+        assert_eq!(restore(&store, &mut south_again, "south").unwrap(), 1);
+        assert_eq!(south_again.reservations()[0].guest, GuestId::new(2));
+    }
+
+    #[test]
+    fn torn_last_record_is_dropped() {
+        let directory = TestDir::new("torn");
+        {
+            let mut store = FileStore::open(&directory.0).unwrap();
+            book(&mut store, &mut Hotel::new("Inn"), "inn", GuestId::new(1), date(3), 2).unwrap();
+        }
+        let torn = "2\tinn\tCaf\u{e9}".as_bytes();
+        append_raw(&directory.0, &torn[..torn.len() - 1]); // Cut inside the two-byte é
+        let mut store = FileStore::open(&directory.0).unwrap();
+        assert_eq!(store.load().unwrap().len(), 1);
+        book(&mut store, &mut Hotel::new("Inn"), "inn", GuestId::new(2), date(8), 1).unwrap(); // Starts on a clean line
+        drop(store);
+        assert_eq!(FileStore::open(&directory.0).unwrap().load().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn lock_keeps_out_a_second_writer() {
+        let directory = TestDir::new("lock");
+        let store = FileStore::open(&directory.0).unwrap();
+        assert!(matches!(FileStore::open(&directory.0), Err(StorageError::Locked { .. })));
+        drop(store);
+        assert!(!directory.0.join(LOCK_FILE).exists());
+        assert!(FileStore::open(&directory.0).is_ok());
+    }
+
+    #[test]
+    fn foreign_appends_are_detected() {
+        let directory = TestDir::new("concurrent");
+        let mut store = FileStore::open(&directory.0).unwrap();
+        let mut hotel = Hotel::new("Inn");
+        book(&mut store, &mut hotel, "inn", GuestId::new(1), date(3), 2).unwrap();
+        append_raw(&directory.0, b"2\tinn\tInn\t2\t20270120\t1\n");
+        assert!(matches!(book(&mut store, &mut hotel, "inn", GuestId::new(3), date(8), 1), Err(StorageError::ConcurrentWrite)));
+        assert!(matches!(store.snapshot(), Err(StorageError::ConcurrentWrite)));
+        assert_eq!(hotel.reservations().len(), 1); // Refused before it was booked
+    }
+
+    #[test]
+    fn out_of_order_sequence_is_corrupt() {
+        let directory = TestDir::new("corrupt");
+        drop(FileStore::open(&directory.0).unwrap());
+        append_raw(&directory.0, b"1\tinn\tInn\t1\t20270103\t2\n3\tinn\tInn\t2\t20270108\t1\n");
+        assert!(matches!(FileStore::open(&directory.0), Err(StorageError::Corrupt { line: 2, .. })));
+        assert!(!directory.0.join(LOCK_FILE).exists()); // A failed open gives the lock back
+    }
+}
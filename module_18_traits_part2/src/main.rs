@@ -1,17 +1,156 @@
 use module_18_traits_part2::lodging::{Hotel, AirBnB, Accommodation, Description};
+use module_18_traits_part2::lodging::folio::{self, Extra, Folio};
+use module_18_traits_part2::lodging::calendar::{Calendar, Date};
+use module_18_traits_part2::lodging::guest::GuestRegistry;
+use module_18_traits_part2::lodging::ical;
+use module_18_traits_part2::lodging::review::{self, Reviewable};
+use module_18_traits_part2::lodging::storage::{self, FileStore, InMemoryStore, ReservationStore};
 use module_18_traits_part2::utils;
 
 
 fn main() {
     // We have to make the traits public to use their methods here! If a trait is public, its methods are also public. The same is not the case for structs!
+    let mut guests = GuestRegistry::new();
+    let dana = guests.register("Dana", "dana@example.com", "555-0101");
+    let dan = guests.register("Dan", "dan@example.com", "555-0102");
+    let phil = guests.register("Phil", "phil@example.com", "555-0103");
+
+    let october = |day| Date::new(2026, 10, day).unwrap();
+    let november = |day| Date::new(2026, 11, day).unwrap();
+
     let mut hotel = Hotel::new(String::from("The Luxe"));
     println!("{}", hotel.summarize());
-    hotel.book("Dana", 5);
+    hotel.book(dana, october(1), 5).unwrap();
 
     let mut airbnb = AirBnB::new("Parker".to_string());
     println!("{}", airbnb.get_description());
-    utils::book_for_one_night(&mut airbnb, "Dan");
-    utils::mix_and_match(&mut hotel, &mut airbnb, "Phil");
+    utils::book_for_one_night(&mut airbnb, dan, october(1)).unwrap();
+    utils::mix_and_match(&mut hotel, &mut airbnb, phil, october(6)).unwrap();
+    if let Err(error) = hotel.book(dan, october(3), 2) { // Booking directly still can't double-book
+        println!("{}", error);
+    }
 
     println!("Associated Constraints in a Trait");
+
+    println!("Guest Profiles and Loyalty Points");
+    guests.book(&mut hotel, dana, october(10), 7).unwrap(); // 7 paid nights earn 700 points
+    guests.book(&mut airbnb, dana, october(20), 4).unwrap(); // 4 more nights brings Dana to 1100 points
+    match guests.book_with_points(&mut hotel, dana, november(1), 3, 1) { // Redeem 1000 points for one of the three nights
+        Ok(stay) => println!("Booked {} nights at {} ({} free)", stay.nights, stay.property, stay.redeemed_nights),
+        Err(error) => println!("Booking failed: {}", error),
+    }
+    if let Err(error) = guests.book_with_points(&mut airbnb, dan, november(1), 2, 1) { // Dan has no points yet
+        println!("Booking failed: {}", error);
+    }
+    if let Err(error) = guests.book(&mut hotel, dan, october(12), 2) { // Overlaps Dana's stay
+        println!("Booking failed: {}", error);
+    }
+
+    if let Some(guest) = guests.get(dana) {
+        println!("{}", guest);
+        for stay in guest.history() {
+            println!("  {:?}", stay);
+        }
+    }
+
+    println!("Reviews and Ratings");
+    if let Err(error) = review::leave_review(&guests, &mut hotel, dana, 5, "Spotless rooms") { // Dana hasn't checked out yet
+        println!("Review rejected: {}", error);
+    }
+    guests.check_out(dana, &hotel).unwrap();
+    guests.check_out(dana, &hotel).unwrap();
+    review::leave_review(&guests, &mut hotel, dana, 5, "Spotless rooms").unwrap();
+    review::leave_review(&guests, &mut hotel, dana, 4, "Great, but breakfast was cold").unwrap();
+    if let Err(error) = review::leave_review(&guests, &mut hotel, dana, 1, "Third time") { // Only two completed stays
+        println!("Review rejected: {}", error);
+    }
+    if let Err(error) = review::leave_review(&guests, &mut airbnb, dana, 9, "Amazing") {
+        println!("Review rejected: {}", error);
+    }
+    for entry in hotel.reviews() {
+        println!("  {}", entry);
+    }
+    println!("{} reviews, average {:?}", hotel.review_count(), hotel.average_rating());
+    println!("{}", hotel.summarize());
+
+    println!("iCalendar Import and Export");
+    let exported = ical::export(&hotel);
+    print!("{}", exported);
+    let round_trip = ical::parse_blocked_dates(&exported).unwrap();
+    let reserved: Vec<_> = hotel.reservations().iter().map(|reservation| reservation.dates()).collect();
+    println!("Round trip matches reservations --> {}", round_trip == reserved);
+
+    // Another platform's feed: a folded SUMMARY line, an all-day event, a timed event and a cancelled one
+    let feed = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261201\r\nDTEND;VALUE=DATE:20261204\r\nSUMMARY:Booked through another\r\n  platform\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART:20261210T150000Z\r\nDTEND:20261212T110000Z\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261220\r\nDURATION:P1W\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n\
+END:VCALENDAR\r\n";
+    let path = std::env::temp_dir().join("parker.ics");
+    std::fs::write(&path, feed).unwrap();
+    println!("Imported {} blocked ranges", ical::import_file(&mut airbnb, &path).unwrap());
+    for range in airbnb.blocked_dates() {
+        println!("  Blocked {}", range);
+    }
+    if let Err(error) = guests.book(&mut airbnb, phil, Date::new(2026, 12, 11).unwrap(), 1) {
+        println!("Booking failed: {}", error);
+    }
+    let _ = std::fs::remove_file(&path);
+
+    println!("Persistent Storage");
+    let mut memory = InMemoryStore::new();
+    storage::book(&mut memory, &mut airbnb, "parker", phil, Date::new(2026, 12, 5).unwrap(), 2).unwrap();
+    println!("In-memory store holds {} reservations", memory.load().unwrap().len());
+
+    let directory = std::env::temp_dir().join("lodging_store");
+    let _ = std::fs::remove_dir_all(&directory);
+    {
+        let mut store = FileStore::open(&directory).unwrap();
+        let mut seaside = Hotel::new(String::from("Seaside Inn"));
+        let mut namesake = Hotel::new(String::from("Seaside Inn")); // Another hotel with the same name, filed under its own key
+        storage::book(&mut store, &mut namesake, "seaside-inn-north", phil, Date::new(2027, 1, 3).unwrap(), 1).unwrap();
+        storage::book(&mut store, &mut seaside, "seaside-inn", dana, Date::new(2027, 1, 3).unwrap(), 4).unwrap();
+        store.snapshot().unwrap();
+        storage::book(&mut store, &mut seaside, "seaside-inn", dan, Date::new(2027, 1, 8).unwrap(), 2).unwrap();
+
+        if let Err(error) = FileStore::open(&directory) { // A second writer is turned away while the first holds the lock
+            println!("Second writer rejected: {}", error);
+        }
+
+        // Simulate another process appending behind our back
+        let mut journal = std::fs::OpenOptions::new().append(true).open(directory.join("journal.log")).unwrap();
+        std::io::Write::write_all(&mut journal, b"4\tseaside-inn\tSeaside Inn\t2\t20270120\t1\n").unwrap();
+        if let Err(error) = storage::book(&mut store, &mut seaside, "seaside-inn", phil, Date::new(2027, 2, 1).unwrap(), 1) {
+            println!("Append refused: {}", error);
+        }
+    } // The store is dropped here, which releases its lock
+
+    let mut restarted = Hotel::new(String::from("Seaside Inn"));
+    let store = FileStore::open(&directory).unwrap();
+    println!("Restored {} reservations after restart", storage::restore(&store, &mut restarted, "seaside-inn").unwrap());
+    for reservation in restarted.reservations() {
+        println!("  {:?}", reservation);
+    }
+    drop(store);
+    let _ = std::fs::remove_dir_all(&directory);
+
+    println!("Invoices and Folios");
+    airbnb.set_nightly_rate(12000);
+    let stay = airbnb.reservations().iter().find(|reservation| reservation.guest == dana).copied().unwrap();
+    let mut folio = Folio::open(&airbnb, &stay, 0).unwrap();
+    folio.add_extra(Extra::Minibar, 3, 450).unwrap();
+    folio.add_extra(Extra::Parking, stay.nights, 2500).unwrap();
+    folio.add_extra(Extra::Other(String::from("Late \"check-out\"")), 1, 3000).unwrap();
+    println!("Running balance: {}", folio::format_cents(folio.balance_cents().unwrap()));
+    if let Err(error) = folio.add_extra(Extra::RoomService, u32::MAX, u64::MAX / 2) {
+        println!("Extra refused: {}", error);
+    }
+    let invoice = folio.close().unwrap();
+    print!("{}", invoice);
+    println!("{}", invoice.to_json());
+
+    // The night Dana paid for with points is posted at zero instead of being billed again
+    let points_stay = guests.get(dana).unwrap().history().iter().find(|stay| stay.redeemed_nights > 0).cloned().unwrap();
+    let reservation = hotel.reservations().iter().find(|reservation| reservation.check_in == points_stay.check_in).copied().unwrap();
+    print!("{}", Folio::open(&hotel, &reservation, points_stay.redeemed_nights).unwrap().close().unwrap());
 }
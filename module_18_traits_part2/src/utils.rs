@@ -1,17 +1,21 @@
 // use super::lodging::{Accommodation, Description}; // Can also do it this way
-use crate::lodging::{Accommodation, Description};
+use crate::lodging::{Accommodation, Description, Unavailable};
+use crate::lodging::calendar::Date;
+use crate::lodging::guest::GuestId;
 
-pub fn book_for_one_night<T: Accommodation + Description>(entity: &mut T, guest: &str) {
-    entity.book(guest, 1);
+pub fn book_for_one_night<T: Accommodation + Description>(entity: &mut T, guest: GuestId, check_in: Date) -> Result<(), Unavailable> {
+    entity.book(guest, check_in, 1)?;
+    Ok(())
 }
 
-pub fn mix_and_match<T, U>(first: &mut T, second: &mut U, guest: &str)
+pub fn mix_and_match<T, U>(first: &mut T, second: &mut U, guest: GuestId, check_in: Date) -> Result<(), Unavailable>
 where
     T: Accommodation + Description,
     U: Accommodation,
 {
-    first.book(guest, 1);
+    first.book(guest, check_in, 1)?;
     first.get_description();
 
-    second.book(guest, 1);
+    second.book(guest, check_in, 1)?;
+    Ok(())
 }
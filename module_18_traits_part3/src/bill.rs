@@ -0,0 +1,208 @@
+// Splitting a group check. The check holds Lunch items, each tagged with whoever shared it, and works out tax and tip on
+// the food subtotal. Every split hands out food, tax and tip separately with Money::allocate, so each column, and so
+// each person's total, adds up to the exact amount on the check.
+use std::fmt::{Display, Formatter};
+
+use crate::Lunch;
+use crate::money::{Currency, Money, MoneyError, Rounding};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BillError {
+    NoDiners,
+    UnknownDiner(String),
+    NobodyOrdered(String), // An item with no one to charge for it
+    SharesMismatch { diners: usize, shares: usize },
+    Money(MoneyError),
+}
+
+impl Display for BillError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BillError::NoDiners => write!(f, "The check has nobody to split it between"),
+            BillError::UnknownDiner(name) => write!(f, "{} isn't at the table", name),
+            BillError::NobodyOrdered(item) => write!(f, "Nobody is listed for the {}", item),
+            BillError::SharesMismatch { diners, shares } => write!(f, "{} shares given for {} diners", shares, diners),
+            BillError::Money(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<MoneyError> for BillError {
+    fn from(error: MoneyError) -> Self {
+        BillError::Money(error)
+    }
+}
+
+#[derive(Debug)]
+pub struct CheckItem {
+    pub name: String,
+    pub lunch: Lunch,
+    pub diners: Vec<usize>, // Indexes into the check's diners, the cost is split evenly between them
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Split {
+    Even,
+    ByItem,             // Everyone pays for what they had, tax and tip in proportion to that
+    ByShares(Vec<u64>), // One share count per diner, in the order they were seated
+}
+
+#[derive(Debug)]
+pub struct Check {
+    currency: Currency,
+    diners: Vec<String>,
+    items: Vec<CheckItem>,
+    tax_basis_points: i64, // 875 is 8.75%
+    tip_percent: i64,      // Of the food subtotal, before tax
+}
+
+impl Check {
+    pub fn new(currency: Currency, diners: &[&str]) -> Self {
+        Self {
+            currency,
+            diners: diners.iter().map(|name| name.to_string()).collect(),
+            items: Vec::new(),
+            tax_basis_points: 0,
+            tip_percent: 0,
+        }
+    }
+
+    pub fn set_tax(&mut self, basis_points: i64) {
+        self.tax_basis_points = basis_points;
+    }
+
+    pub fn set_tip(&mut self, percent: i64) {
+        self.tip_percent = percent;
+    }
+
+    pub fn add(&mut self, name: &str, lunch: Lunch, diners: &[&str]) -> Result<(), BillError> {
+        if diners.is_empty() {
+            return Err(BillError::NobodyOrdered(name.to_string()));
+        }
+        Money::zero(self.currency).checked_add(lunch.cost)?; // Rejects a lunch priced in another currency
+        let diners = diners
+            .iter()
+            .map(|diner| self.diners.iter().position(|seated| seated.eq_ignore_ascii_case(diner)).ok_or_else(|| BillError::UnknownDiner(diner.to_string())))
+            .collect::<Result<Vec<usize>, BillError>>()?;
+        self.items.push(CheckItem { name: name.to_string(), lunch, diners });
+        Ok(())
+    }
+
+    pub fn subtotal(&self) -> Money {
+        self.items.iter().fold(Money::zero(self.currency), |total, item| total + item.lunch.cost)
+    }
+
+    pub fn tax(&self) -> Result<Money, BillError> {
+        Ok(self.subtotal().mul_ratio(self.tax_basis_points, 10_000, Rounding::HalfUp)?)
+    }
+
+    pub fn tip(&self) -> Result<Money, BillError> {
+        Ok(self.subtotal().mul_ratio(self.tip_percent, 100, Rounding::HalfUp)?)
+    }
+
+    // What each diner's plate cost, with shared items divided between the people who shared them
+    fn food_by_diner(&self) -> Result<Vec<Money>, BillError> {
+        let mut food = vec![Money::zero(self.currency); self.diners.len()];
+        for item in &self.items {
+            let parts = item.lunch.cost.split(item.diners.len() as u32)?;
+            for (&diner, part) in item.diners.iter().zip(parts) {
+                food[diner] += part;
+            }
+        }
+        Ok(food)
+    }
+
+    pub fn split(&self, split: &Split) -> Result<SplitBill, BillError> {
+        if self.diners.is_empty() {
+            return Err(BillError::NoDiners);
+        }
+        let (subtotal, tax, tip) = (self.subtotal(), self.tax()?, self.tip()?);
+        let (food, ratios): (Vec<Money>, Vec<u64>) = match split {
+            Split::Even => (subtotal.split(self.diners.len() as u32)?, vec![1; self.diners.len()]),
+            Split::ByShares(shares) => {
+                if shares.len() != self.diners.len() {
+                    return Err(BillError::SharesMismatch { diners: self.diners.len(), shares: shares.len() });
+                }
+                (subtotal.allocate(shares)?, shares.clone())
+            }
+            Split::ByItem => {
+                let food = self.food_by_diner()?;
+                let ratios = food.iter().map(|plate| plate.minor().max(0) as u64).collect();
+                (food, ratios)
+            }
+        };
+
+        // Nobody had anything (an empty check or nothing but free items), so tax and tip are split evenly
+        let ratios = if ratios.iter().all(|&ratio| ratio == 0) { vec![1; self.diners.len()] } else { ratios };
+        let taxes = tax.allocate(&ratios)?;
+        let tips = tip.allocate(&ratios)?;
+        let people = self
+            .diners
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Share { name: name.clone(), food: food[i], tax: taxes[i], tip: tips[i] })
+            .collect();
+        Ok(SplitBill { method: split.clone(), people, subtotal, tax, tip })
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "** Check **")?;
+        for item in &self.items {
+            let diners: Vec<&str> = item.diners.iter().map(|&diner| self.diners[diner].as_str()).collect();
+            writeln!(f, "{:<12} {:>10}  {}", item.name, item.lunch.cost, diners.join(", "))?;
+        }
+        writeln!(f, "{:<12} {:>10}", "Subtotal", self.subtotal())?;
+        if let (Ok(tax), Ok(tip)) = (self.tax(), self.tip()) {
+            writeln!(f, "{:<12} {:>10}", "Tax", tax)?;
+            write!(f, "{:<12} {:>10}", "Tip", tip)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+    pub name: String,
+    pub food: Money,
+    pub tax: Money,
+    pub tip: Money,
+}
+
+impl Share {
+    pub fn total(&self) -> Money {
+        self.food + self.tax + self.tip
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitBill {
+    pub method: Split,
+    pub people: Vec<Share>,
+    pub subtotal: Money,
+    pub tax: Money,
+    pub tip: Money,
+}
+
+impl SplitBill {
+    pub fn total(&self) -> Money {
+        self.subtotal + self.tax + self.tip
+    }
+
+    // Always true, the per-person totals are built to add back up to the check
+    pub fn is_balanced(&self) -> bool {
+        self.people.iter().fold(Money::zero(self.total().currency()), |sum, share| sum + share.total()) == self.total()
+    }
+}
+
+impl Display for SplitBill {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "** Check split {:?} **", self.method)?;
+        writeln!(f, "{:<10} {:>10} {:>10} {:>10} {:>10}", "Diner", "Food", "Tax", "Tip", "Owes")?;
+        for share in &self.people {
+            writeln!(f, "{:<10} {:>10} {:>10} {:>10} {:>10}", share.name, share.food, share.tax, share.tip, share.total())?;
+        }
+        write!(f, "{:<10} {:>10} {:>10} {:>10} {:>10}", "Check", self.subtotal, self.tax, self.tip, self.total())
+    }
+}
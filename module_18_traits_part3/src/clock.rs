@@ -0,0 +1,86 @@
+// Times of day as minutes since midnight, parsed from the strings the structs in main.rs carry ("10:00 AM", "22:15").
+// Anything that runs past midnight keeps counting up, so 25:30 is 1:30 AM the next day.
+use std::fmt::{Display, Formatter};
+
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+
+// A length of time rather than a time of day, e.g. 95 minutes is "1h35m"
+pub fn hours_minutes(minutes: f64) -> String {
+    let minutes = minutes.round() as i64;
+    format!("{}h{:02}m", minutes / 60, minutes % 60)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Clock {
+    minutes: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClockError(pub String); // The text that couldn't be read as a time
+
+impl Display for ClockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is not a time like 9:30 AM or 21:30", self.0)
+    }
+}
+
+impl Clock {
+    pub fn hm(hours: u32, minutes: u32) -> Self {
+        Self { minutes: hours * 60 + minutes }
+    }
+
+    // 12 or 24 hour, e.g. "10:00 AM", "10:00pm", "8:30" or "22:15"
+    pub fn parse(text: &str) -> Result<Self, ClockError> {
+        let error = || ClockError(text.to_string());
+        let trimmed = text.trim().to_ascii_uppercase();
+        let (time, meridiem) = if let Some(time) = trimmed.strip_suffix("AM") {
+            (time.trim_end(), Some(false))
+        } else if let Some(time) = trimmed.strip_suffix("PM") {
+            (time.trim_end(), Some(true))
+        } else {
+            (trimmed.as_str(), None)
+        };
+        let (hours, minutes) = time.split_once(':').ok_or_else(error)?;
+        let hours: u32 = hours.parse().map_err(|_| error())?;
+        let minutes: u32 = minutes.parse().map_err(|_| error())?;
+        if minutes > 59 {
+            return Err(error());
+        }
+        let hours = match meridiem {
+            Some(pm) if (1..=12).contains(&hours) => hours % 12 + if pm { 12 } else { 0 },
+            Some(_) => return Err(error()),
+            None if hours < 24 => hours,
+            None => return Err(error()),
+        };
+        Ok(Self::hm(hours, minutes))
+    }
+
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+
+    pub fn add_minutes(&self, minutes: u32) -> Self {
+        Self { minutes: self.minutes + minutes }
+    }
+
+    // None if the total doesn't fit in a u32
+    pub fn checked_add_minutes(&self, minutes: u32) -> Option<Self> {
+        Some(Self { minutes: self.minutes.checked_add(minutes)? })
+    }
+
+    pub fn minutes_until(&self, later: Clock) -> Option<u32> {
+        later.minutes.checked_sub(self.minutes)
+    }
+}
+
+impl Display for Clock { // 24 hour, with "+1" for each day past the first
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let days = self.minutes / MINUTES_PER_DAY;
+        let minutes = self.minutes % MINUTES_PER_DAY;
+        write!(f, "{:02}:{:02}", minutes / 60, minutes % 60)?;
+        if days > 0 {
+            write!(f, "+{}", days)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,332 @@
+// Arithmetic, comparison, parsing and formatting for Duration. A Duration is kept normalized (minutes and seconds below
+// 60) whenever the hours fit in a u32, and comparisons go by the total, so two equal lengths of time always compare equal. Like std::time::Duration, the
+// operators panic when a result would overflow or go negative, and the checked_ methods return a DurationError instead.
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+use crate::Duration;
+
+const MAX_SECONDS: u64 = u32::MAX as u64 * 3600 + 3599; // u32::MAX hours, 59 minutes and 59 seconds
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DurationError {
+    Overflow,
+    Negative { short_by: Duration }, // Subtracting a longer duration from a shorter one
+    Parse(String),
+}
+
+impl Display for DurationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DurationError::Overflow => write!(f, "The duration is longer than {} hours", u32::MAX),
+            DurationError::Negative { short_by } => write!(f, "The result would be negative by {}", short_by),
+            DurationError::Parse(text) => write!(f, "'{}' is not a duration like 1h30m, 01:30:00 or PT1H30M", text),
+        }
+    }
+}
+
+impl Duration {
+    // Carries seconds past 59 into minutes and minutes past 59 into hours
+    pub fn try_new(hours: u32, minutes: u32, seconds: u32) -> Result<Self, DurationError> {
+        Self::from_seconds(hours as u64 * 3600 + minutes as u64 * 60 + seconds as u64)
+    }
+
+    pub fn from_seconds(total: u64) -> Result<Self, DurationError> {
+        if total > MAX_SECONDS {
+            return Err(DurationError::Overflow);
+        }
+        Ok(Self { hours: (total / 3600) as u32, minutes: (total % 3600 / 60) as u32, seconds: (total % 60) as u32 })
+    }
+
+    pub fn total_seconds(&self) -> u64 {
+        self.hours as u64 * 3600 + self.minutes as u64 * 60 + self.seconds as u64
+    }
+
+    pub fn checked_add(self, other: Duration) -> Result<Duration, DurationError> {
+        Self::from_seconds(self.total_seconds() + other.total_seconds())
+    }
+
+    pub fn checked_sub(self, other: Duration) -> Result<Duration, DurationError> {
+        match self.total_seconds().checked_sub(other.total_seconds()) {
+            Some(total) => Self::from_seconds(total),
+            None => Err(DurationError::Negative { short_by: other.abs_diff(self) }),
+        }
+    }
+
+    pub fn checked_mul(self, times: u32) -> Result<Duration, DurationError> {
+        let total = self.total_seconds().checked_mul(times as u64).ok_or(DurationError::Overflow)?;
+        Self::from_seconds(total)
+    }
+
+    // Zero instead of going negative
+    pub fn saturating_sub(self, other: Duration) -> Duration {
+        self.checked_sub(other).unwrap_or(Duration { hours: 0, minutes: 0, seconds: 0 })
+    }
+
+    pub fn abs_diff(self, other: Duration) -> Duration {
+        let total = self.total_seconds().abs_diff(other.total_seconds());
+        Self::from_seconds(total).unwrap_or_else(|error| panic!("{}", error)) // Never larger than either side
+    }
+
+    // "01:30:00", hours grow past two digits when they need to
+    pub fn to_clock_string(self) -> String {
+        let normal = self.normalized();
+        format!("{:02}:{:02}:{:02}", normal.hours, normal.minutes, normal.seconds)
+    }
+
+    // ISO 8601, e.g. "PT1H30M". Zero is "PT0S".
+    pub fn to_iso8601(self) -> String {
+        let normal = self.normalized();
+        if normal.total_seconds() == 0 {
+            return String::from("PT0S");
+        }
+        let mut text = String::from("PT");
+        for (value, unit) in [(normal.hours, 'H'), (normal.minutes, 'M'), (normal.seconds, 'S')] {
+            if value > 0 {
+                text.push_str(&format!("{}{}", value, unit));
+            }
+        }
+        text
+    }
+
+    // Struct literals can skip try_new, so anything that reads the fields normalizes first
+    fn normalized(&self) -> Duration {
+        Self::from_seconds(self.total_seconds()).unwrap_or(*self)
+    }
+
+    // "1h30m", "45s", "2h" or "1h5m30s"
+    fn parse_units(text: &str) -> Option<u64> {
+        let mut total = 0u64;
+        let mut digits = String::new();
+        let mut last_unit = 0; // Units have to come in order: d, h, m, then s
+        for c in text.chars() {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+            let (rank, seconds) = match c.to_ascii_lowercase() {
+                'd' => (1, 86400),
+                'h' => (2, 3600),
+                'm' => (3, 60),
+                's' => (4, 1),
+                _ => return None,
+            };
+            if digits.is_empty() || rank <= last_unit {
+                return None;
+            }
+            total = total.checked_add(digits.parse::<u64>().ok()?.checked_mul(seconds)?)?;
+            digits.clear();
+            last_unit = rank;
+        }
+        if !digits.is_empty() || last_unit == 0 {
+            return None;
+        }
+        Some(total)
+    }
+
+    // "01:30:00" or "1:30" (hours and minutes)
+    fn parse_clock(text: &str) -> Option<u64> {
+        let parts: Vec<u64> = text.split(':').map(|part| part.parse().ok()).collect::<Option<Vec<u64>>>()?;
+        match parts[..] {
+            [hours, minutes] if minutes < 60 => hours.checked_mul(3600)?.checked_add(minutes * 60),
+            [hours, minutes, seconds] if minutes < 60 && seconds < 60 => hours.checked_mul(3600)?.checked_add(minutes * 60 + seconds),
+            _ => None,
+        }
+    }
+
+    // "PT1H30M", "P1DT2H" or "PT45S"
+    fn parse_iso8601(text: &str) -> Option<u64> {
+        let rest = text.strip_prefix('P')?;
+        let (days, time) = match rest.split_once('T') {
+            Some((days, time)) if !time.is_empty() => (days, Some(time)),
+            Some(_) => return None,
+            None => (rest, None),
+        };
+        let days = match days {
+            "" => 0,
+            days => days.strip_suffix('D')?.parse::<u64>().ok()?.checked_mul(86400)?,
+        };
+        let time = match time {
+            Some(time) if !time.contains('D') => Self::parse_units(time)?,
+            Some(_) => return None,
+            None if days == 0 => return None, // A bare "P"
+            None => 0,
+        };
+        days.checked_add(time)
+    }
+}
+
+impl FromStr for Duration {
+    type Err = DurationError;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let trimmed = text.trim();
+        let seconds = if trimmed.starts_with(['P', 'p']) {
+            Self::parse_iso8601(&trimmed.to_ascii_uppercase())
+        } else if trimmed.contains(':') {
+            Self::parse_clock(trimmed)
+        } else {
+            Self::parse_units(trimmed)
+        };
+        let seconds = seconds.ok_or_else(|| DurationError::Parse(text.to_string()))?;
+        Self::from_seconds(seconds)
+    }
+}
+
+impl Display for Duration { // Compact, e.g. "1h30m", zero is "0s"
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let normal = self.normalized();
+        let mut text = String::new();
+        for (value, unit) in [(normal.hours, 'h'), (normal.minutes, 'm'), (normal.seconds, 's')] {
+            if value > 0 {
+                text.push_str(&format!("{}{}", value, unit));
+            }
+        }
+        if text.is_empty() {
+            text.push_str("0s");
+        }
+        f.pad(&text)
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl Mul<u32> for Duration {
+    type Output = Duration;
+    fn mul(self, rhs: u32) -> Self::Output {
+        self.checked_mul(rhs).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+// Equality and ordering go by the total length, so 0h90m equals 1h30m even if it was built without try_new
+impl PartialEq for Duration {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_seconds() == other.total_seconds()
+    }
+}
+
+impl Eq for Duration {}
+
+impl Hash for Duration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.total_seconds().hash(state);
+    }
+}
+
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Duration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_seconds().cmp(&other.total_seconds())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hms(hours: u32, minutes: u32, seconds: u32) -> Duration {
+        Duration { hours, minutes, seconds }
+    }
+
+    #[test]
+    fn parses_all_three_formats() {
+        for text in ["1h30m", "90m", "5400s", "01:30:00", "1:30", "PT1H30M", "pt90m", " PT5400S "] {
+            assert_eq!(text.parse::<Duration>(), Ok(hms(1, 30, 0)), "{}", text);
+        }
+        assert_eq!("1d2h".parse::<Duration>(), Ok(hms(26, 0, 0)));
+        assert_eq!("P1DT2H".parse::<Duration>(), Ok(hms(26, 0, 0)));
+        assert_eq!("P2D".parse::<Duration>(), Ok(hms(48, 0, 0)));
+        assert_eq!("100:00:05".parse::<Duration>(), Ok(hms(100, 0, 5)));
+    }
+
+    #[test]
+    fn rejects_malformed_text() {
+        for text in ["", "90", "1m30h", "1h1h", "h", "1x", "1:75", "1:30:60", "1:2:3:4", "P", "PT", "P1H", "PT1D", "-1h"] {
+            assert_eq!(text.parse::<Duration>(), Err(DurationError::Parse(text.to_string())), "{}", text);
+        }
+        assert_eq!(format!("{}h", u32::MAX as u64 + 1).parse::<Duration>(), Err(DurationError::Overflow));
+        assert!("99999999999999999999h".parse::<Duration>().is_err());
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        for duration in [hms(0, 0, 0), hms(0, 0, 45), hms(1, 30, 0), hms(26, 0, 5), hms(123, 4, 5)] {
+            for text in [duration.to_string(), duration.to_clock_string(), duration.to_iso8601()] {
+                assert_eq!(text.parse::<Duration>(), Ok(duration), "{}", text);
+            }
+        }
+        assert_eq!(hms(0, 0, 0).to_string(), "0s");
+        assert_eq!(hms(0, 0, 0).to_iso8601(), "PT0S");
+        assert_eq!(hms(1, 0, 5).to_string(), "1h5s");
+        assert_eq!(hms(123, 4, 5).to_clock_string(), "123:04:05");
+        assert_eq!(format!("[{:>6}]", hms(1, 30, 0)), "[ 1h30m]");
+    }
+
+    #[test]
+    fn normalizes_and_compares_by_total() {
+        let carried = Duration::try_new(0, 90, 75).unwrap();
+        assert_eq!((carried.hours, carried.minutes, carried.seconds), (1, 31, 15));
+        let raw = hms(0, 90, 0); // A struct literal skips try_new
+        assert_eq!(raw, hms(1, 30, 0));
+        assert_eq!(raw.to_string(), "1h30m");
+        assert_eq!(raw.to_clock_string(), "01:30:00");
+        assert!(hms(0, 59, 60) > hms(0, 59, 59));
+        assert_eq!(Duration::try_new(u32::MAX, 60, 0), Err(DurationError::Overflow));
+        assert_eq!(Duration::try_new(u32::MAX, 59, 59).map(|d| d.total_seconds()), Ok(MAX_SECONDS));
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_overflow() {
+        let longest = Duration::from_seconds(MAX_SECONDS).unwrap();
+        assert_eq!(longest.checked_add(hms(0, 0, 1)), Err(DurationError::Overflow));
+        assert_eq!(longest.checked_add(hms(0, 0, 0)), Ok(longest));
+        assert_eq!(hms(2, 0, 0).checked_mul(u32::MAX), Err(DurationError::Overflow));
+        assert_eq!(hms(0, 0, 1).checked_mul(u32::MAX), Ok(hms(0, 0, u32::MAX)));
+        assert_eq!(hms(1, 30, 0).checked_mul(0), Ok(hms(0, 0, 0)));
+        assert_eq!(hms(1, 0, 0) + hms(0, 90, 0), hms(2, 30, 0));
+        assert_eq!(hms(0, 45, 0) * 4, hms(3, 0, 0));
+    }
+
+    #[test]
+    fn subtraction_never_goes_negative() {
+        assert_eq!(hms(1, 30, 0).checked_sub(hms(1, 0, 0)), Ok(hms(0, 30, 0)));
+        assert_eq!(hms(1, 0, 0).checked_sub(hms(1, 30, 0)), Err(DurationError::Negative { short_by: hms(0, 30, 0) }));
+        assert_eq!(hms(1, 0, 0).saturating_sub(hms(1, 30, 0)), hms(0, 0, 0));
+        assert_eq!(hms(1, 0, 0).abs_diff(hms(1, 30, 0)), hms(0, 30, 0));
+        assert_eq!(hms(1, 0, 0) - hms(1, 0, 0), hms(0, 0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "negative")]
+    fn sub_operator_panics_when_negative() {
+        let _ = hms(0, 1, 0) - hms(0, 2, 0);
+    }
+
+    #[test]
+    fn new_keeps_fields_it_cannot_carry() {
+        let huge = Duration::new(u32::MAX, 60, 0);
+        assert_eq!((huge.hours, huge.minutes, huge.seconds), (u32::MAX, 60, 0));
+        assert_eq!(huge.total_seconds(), MAX_SECONDS + 1);
+        assert_eq!(huge.checked_add(hms(0, 0, 0)), Err(DurationError::Overflow));
+        assert_eq!(Duration::new(0, 90, 0), hms(1, 30, 0));
+    }
+}
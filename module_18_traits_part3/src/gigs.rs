@@ -0,0 +1,437 @@
+// Booking musicians into venues. Each act wraps a Musician with a stage name, genres and a fee, and the size of the
+// musician (one singer-songwriter or a band of N) decides how much stage and how many inputs they need. A gig is only
+// booked if the venue's stage is big enough, neither the act nor the venue is already booked at that time, and the
+// setlist fits in the slot. Times reuse Clock and dates reuse the produce Date, so a set can run past midnight.
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use crate::clock::{Clock, ClockError, MINUTES_PER_DAY};
+use crate::duration::DurationError;
+use crate::money::{Money, MoneyError, Rounding};
+use crate::produce::Date;
+use crate::{Band, Duration, Musician, SingerSongWriter};
+
+// Stage space and mixer inputs per person on stage, plus what every act needs regardless of size
+const STAGE_M2_PER_PERFORMER: u32 = 4;
+const STAGE_M2_BASE: u32 = 6; // Monitors, amps and room to walk on and off
+const INPUTS_PER_PERFORMER: u32 = 3;
+const INPUTS_BASE: u32 = 2; // Playback and the announcement mic
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Genre {
+    Rock,
+    Pop,
+    Folk,
+    Country,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageNeeds {
+    pub performers: u32,
+    pub stage_m2: u32,
+    pub inputs: u32,
+}
+
+impl Musician {
+    pub fn performers(&self) -> u32 {
+        match self {
+            SingerSongWriter(_) => 1,
+            Band(members) => *members,
+        }
+    }
+
+    // Saturates for absurdly large bands, which no venue can fit either way
+    pub fn stage_needs(&self) -> StageNeeds {
+        let performers = self.performers();
+        StageNeeds {
+            performers,
+            stage_m2: performers.saturating_mul(STAGE_M2_PER_PERFORMER).saturating_add(STAGE_M2_BASE),
+            inputs: performers.saturating_mul(INPUTS_PER_PERFORMER).saturating_add(INPUTS_BASE),
+        }
+    }
+}
+
+pub struct Act {
+    pub name: String,
+    pub musician: Musician,
+    pub genres: Vec<Genre>,
+    pub fee: Money, // Per gig
+}
+
+impl Act {
+    pub fn new(name: &str, musician: Musician, genres: &[Genre], fee: Money) -> Self {
+        Self { name: name.to_string(), musician, genres: genres.to_vec(), fee }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Venue {
+    pub name: String,
+    pub capacity: u32, // Audience, not performers
+    pub stage_m2: u32,
+    pub inputs: u32,
+}
+
+impl Venue {
+    pub fn new(name: &str, capacity: u32, stage_m2: u32, inputs: u32) -> Self {
+        Self { name: name.to_string(), capacity, stage_m2, inputs }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Song {
+    pub title: String,
+    pub length: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setlist {
+    songs: Vec<Song>,
+    gap: Duration, // Tuning and talking between songs
+}
+
+impl Setlist {
+    pub fn new(gap: Duration) -> Self {
+        Self { songs: Vec::new(), gap }
+    }
+
+    pub fn add(&mut self, title: &str, length: &str) -> Result<(), GigError> {
+        let length = length.parse::<Duration>().map_err(|_| GigError::SongLength(title.to_string(), length.to_string()))?;
+        self.songs.push(Song { title: title.to_string(), length });
+        Ok(())
+    }
+
+    pub fn songs(&self) -> &[Song] {
+        &self.songs
+    }
+
+    // All the songs plus a gap between each pair of them
+    pub fn length(&self) -> Result<Duration, GigError> {
+        let gaps = u32::try_from(self.songs.len().saturating_sub(1)).map_err(|_| DurationError::Overflow)?;
+        let gaps = self.gap.checked_mul(gaps)?;
+        Ok(self.songs.iter().try_fold(gaps, |total, song| total.checked_add(song.length))?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GigId(u32);
+
+impl Display for GigId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gig {
+    pub id: GigId,
+    pub act: String,
+    pub venue: String,
+    pub date: Date,
+    pub start: Clock,
+    pub end: Clock,
+    pub setlist: Setlist,
+}
+
+impl Gig {
+    fn overlaps(&self, date: Date, start: Clock, end: Clock) -> bool {
+        // Move both onto the same timeline, so a set after midnight clashes with the next day's early show
+        let offset = |day: Date| date.days_until(day) * MINUTES_PER_DAY as i64;
+        let (this_start, this_end) = (offset(self.date) + self.start.minutes() as i64, offset(self.date) + self.end.minutes() as i64);
+        (start.minutes() as i64) < this_end && this_start < end.minutes() as i64
+    }
+}
+
+impl Display for Gig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} at {}, {} {}-{} ({} songs, {})",
+            self.id,
+            self.act,
+            self.venue,
+            self.date,
+            self.start,
+            self.end,
+            self.setlist.songs().len(),
+            self.setlist.length().map_or_else(|error| error.to_string(), |length| length.to_string())
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GigError {
+    Time(ClockError),
+    SongLength(String, String), // Song title and the length that couldn't be read
+    SetlistLength(DurationError),
+    NoPerformers(String),
+    DuplicateAct(String),
+    DuplicateVenue(String),
+    UnknownAct(String),
+    UnknownVenue(String),
+    StageTooSmall { needs: StageNeeds, venue: String },
+    ActBusy(GigId),
+    VenueBusy(GigId),
+    SetlistTooLong { setlist: Duration, slot: Duration },
+    UnknownGig(GigId),
+    Money(MoneyError),
+}
+
+impl Display for GigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GigError::Time(error) => write!(f, "{}", error),
+            GigError::SongLength(title, length) => write!(f, "'{}' can't be {} long", title, length),
+            GigError::SetlistLength(error) => write!(f, "The setlist is too long to add up: {}", error),
+            GigError::NoPerformers(name) => write!(f, "{} has no one to perform", name),
+            GigError::DuplicateAct(name) => write!(f, "There is already an act called {}", name),
+            GigError::DuplicateVenue(name) => write!(f, "There is already a venue called {}", name),
+            GigError::UnknownAct(name) => write!(f, "There is no act called {}", name),
+            GigError::UnknownVenue(name) => write!(f, "There is no venue called {}", name),
+            GigError::StageTooSmall { needs, venue } => write!(
+                f,
+                "{} performers need {}m2 of stage and {} inputs, more than {} has",
+                needs.performers, needs.stage_m2, needs.inputs, venue
+            ),
+            GigError::ActBusy(id) => write!(f, "The act is already playing gig {} then", id),
+            GigError::VenueBusy(id) => write!(f, "The venue already has gig {} then", id),
+            GigError::SetlistTooLong { setlist, slot } => write!(f, "The setlist runs {} but the slot is only {}", setlist, slot),
+            GigError::UnknownGig(id) => write!(f, "There is no gig {}", id),
+            GigError::Money(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<ClockError> for GigError {
+    fn from(error: ClockError) -> Self {
+        GigError::Time(error)
+    }
+}
+
+impl From<DurationError> for GigError {
+    fn from(error: DurationError) -> Self {
+        GigError::SetlistLength(error)
+    }
+}
+
+impl From<MoneyError> for GigError {
+    fn from(error: MoneyError) -> Self {
+        GigError::Money(error)
+    }
+}
+
+#[derive(Default)]
+pub struct Booker {
+    acts: HashMap<String, Act>,
+    venues: HashMap<String, Venue>,
+    gigs: Vec<Gig>, // By date, then start time
+    next_id: u32,
+}
+
+impl Booker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Names are how gigs refer to acts and venues, so an existing one is never replaced
+    pub fn add_act(&mut self, act: Act) -> Result<(), GigError> {
+        if act.musician.performers() == 0 {
+            return Err(GigError::NoPerformers(act.name));
+        }
+        if self.acts.contains_key(&act.name) {
+            return Err(GigError::DuplicateAct(act.name));
+        }
+        self.acts.insert(act.name.clone(), act);
+        Ok(())
+    }
+
+    pub fn add_venue(&mut self, venue: Venue) -> Result<(), GigError> {
+        if self.venues.contains_key(&venue.name) {
+            return Err(GigError::DuplicateVenue(venue.name));
+        }
+        self.venues.insert(venue.name.clone(), venue);
+        Ok(())
+    }
+
+    // Checks the stage, both calendars and the setlist, in that order, and books nothing unless all of them pass
+    pub fn book(&mut self, act: &str, venue: &str, date: Date, start: &str, end: &str, setlist: Setlist) -> Result<GigId, GigError> {
+        let (start, mut end) = (Clock::parse(start)?, Clock::parse(end)?);
+        if end <= start {
+            end = end.add_minutes(MINUTES_PER_DAY); // "10:00 PM" to "1:00 AM" runs past midnight
+        }
+        let performer = self.acts.get(act).ok_or_else(|| GigError::UnknownAct(act.to_string()))?;
+        let stage = self.venues.get(venue).ok_or_else(|| GigError::UnknownVenue(venue.to_string()))?;
+        let needs = performer.musician.stage_needs();
+        if needs.stage_m2 > stage.stage_m2 || needs.inputs > stage.inputs {
+            return Err(GigError::StageTooSmall { needs, venue: venue.to_string() });
+        }
+        for gig in self.gigs.iter().filter(|gig| gig.overlaps(date, start, end)) {
+            if gig.act == act {
+                return Err(GigError::ActBusy(gig.id));
+            }
+            if gig.venue == venue {
+                return Err(GigError::VenueBusy(gig.id));
+            }
+        }
+        let slot = Duration::try_new(0, end.minutes() - start.minutes(), 0)?;
+        let length = setlist.length()?;
+        if length > slot {
+            return Err(GigError::SetlistTooLong { setlist: length, slot });
+        }
+
+        self.next_id += 1;
+        let id = GigId(self.next_id);
+        let gig = Gig { id, act: act.to_string(), venue: venue.to_string(), date, start, end, setlist };
+        let at = self.gigs.partition_point(|existing| (existing.date, existing.start) <= (date, start));
+        self.gigs.insert(at, gig);
+        Ok(id)
+    }
+
+    pub fn cancel(&mut self, id: GigId) -> Result<Gig, GigError> {
+        let at = self.gigs.iter().position(|gig| gig.id == id).ok_or(GigError::UnknownGig(id))?;
+        Ok(self.gigs.remove(at))
+    }
+
+    pub fn gigs(&self) -> &[Gig] {
+        &self.gigs
+    }
+
+    // Acts that play any of the genres, for filling an empty night
+    pub fn acts_for(&self, genres: &[Genre]) -> Vec<&Act> {
+        let mut acts: Vec<&Act> = self.acts.values().filter(|act| act.genres.iter().any(|genre| genres.contains(genre))).collect();
+        acts.sort_by(|a, b| a.name.cmp(&b.name));
+        acts
+    }
+
+    // The cheapest ticket that covers the act's fee if the venue sells out
+    pub fn break_even_ticket(&self, id: GigId) -> Result<Money, GigError> {
+        let gig = self.gigs.iter().find(|gig| gig.id == id).ok_or(GigError::UnknownGig(id))?;
+        let fee = self.acts[&gig.act].fee;
+        let capacity = self.venues[&gig.venue].capacity.max(1);
+        Ok(fee.mul_ratio(1, capacity as i64, Rounding::Ceiling)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Currency;
+
+    fn booker() -> Booker {
+        let mut booker = Booker::new();
+        let fee = Money::from_major(500, Currency::Usd);
+        booker.add_act(Act::new("Solo", SingerSongWriter(String::from("Sam")), &[Genre::Folk], fee)).unwrap();
+        booker.add_act(Act::new("Duo", Band(2), &[Genre::Folk], fee)).unwrap();
+        booker.add_act(Act::new("Big Band", Band(12), &[Genre::Rock], fee)).unwrap();
+        booker.add_venue(Venue::new("Cellar", 80, 20, 16)).unwrap();
+        booker.add_venue(Venue::new("Arena", 10_000, 200, 96)).unwrap();
+        booker
+    }
+
+    fn set(songs: u32) -> Setlist {
+        let mut setlist = Setlist::new(Duration::new(0, 1, 0));
+        for number in 0..songs {
+            setlist.add(&format!("Song {}", number), "4m").unwrap();
+        }
+        setlist
+    }
+
+    fn night(day: u32) -> Date {
+        Date::new(2024, 11, day).unwrap()
+    }
+
+    #[test]
+    fn stage_has_to_fit_the_act() {
+        let mut booker = booker();
+        let needs = Band(12).stage_needs();
+        assert_eq!(
+            booker.book("Big Band", "Cellar", night(8), "8:00 PM", "9:00 PM", set(3)),
+            Err(GigError::StageTooSmall { needs, venue: String::from("Cellar") })
+        );
+        assert!(booker.book("Big Band", "Arena", night(8), "8:00 PM", "9:00 PM", set(3)).is_ok());
+    }
+
+    #[test]
+    fn acts_and_venues_cant_be_double_booked() {
+        let mut booker = booker();
+        let first = booker.book("Solo", "Cellar", night(8), "8:00 PM", "9:00 PM", set(3)).unwrap();
+        assert_eq!(booker.book("Solo", "Arena", night(8), "8:30 PM", "9:30 PM", set(3)), Err(GigError::ActBusy(first)));
+        assert_eq!(booker.book("Duo", "Cellar", night(8), "8:59 PM", "10:00 PM", set(3)), Err(GigError::VenueBusy(first)));
+        assert!(booker.book("Duo", "Cellar", night(8), "9:00 PM", "10:00 PM", set(3)).is_ok()); // Starts as the other ends
+        assert!(booker.book("Duo", "Cellar", night(9), "8:00 PM", "9:00 PM", set(3)).is_ok());
+    }
+
+    #[test]
+    fn sets_past_midnight_clash_with_the_next_day() {
+        let mut booker = booker();
+        let late = booker.book("Solo", "Cellar", night(8), "11:30 PM", "12:30 AM", set(3)).unwrap();
+        assert_eq!(booker.gigs()[0].end, Clock::hm(24, 30));
+        assert_eq!(booker.book("Solo", "Arena", night(9), "12:15 AM", "1:00 AM", set(3)), Err(GigError::ActBusy(late)));
+        assert_eq!(booker.book("Duo", "Cellar", night(9), "12:00 AM", "1:00 AM", set(3)), Err(GigError::VenueBusy(late)));
+        assert!(booker.book("Solo", "Arena", night(9), "12:30 AM", "1:30 AM", set(3)).is_ok());
+        assert!(booker.book("Solo", "Cellar", night(7), "11:00 PM", "11:30 PM", set(3)).is_ok()); // The night before is fine
+    }
+
+    #[test]
+    fn setlist_has_to_fit_the_slot() {
+        let mut booker = booker();
+        let slot = Duration::new(0, 30, 0);
+        assert_eq!(
+            booker.book("Solo", "Cellar", night(8), "8:00 PM", "8:30 PM", set(7)), // 7 x 4m plus 6 gaps is 34m
+            Err(GigError::SetlistTooLong { setlist: Duration::new(0, 34, 0), slot })
+        );
+        assert!(booker.book("Solo", "Cellar", night(8), "8:00 PM", "8:30 PM", set(6)).is_ok()); // 29m
+        assert_eq!(booker.gigs().len(), 1);
+    }
+
+    #[test]
+    fn names_arent_reused() {
+        let mut booker = booker();
+        let fee = Money::from_major(100, Currency::Usd);
+        assert_eq!(booker.add_act(Act::new("Solo", Band(3), &[], fee)), Err(GigError::DuplicateAct(String::from("Solo"))));
+        assert_eq!(booker.add_venue(Venue::new("Cellar", 10, 1, 1)), Err(GigError::DuplicateVenue(String::from("Cellar"))));
+        assert_eq!(booker.venues["Cellar"].stage_m2, 20);
+        assert_eq!(booker.acts["Solo"].musician.performers(), 1);
+    }
+
+    #[test]
+    fn huge_bands_saturate() {
+        let needs = Band(u32::MAX).stage_needs();
+        assert_eq!((needs.stage_m2, needs.inputs), (u32::MAX, u32::MAX));
+        assert_eq!(Band(5).stage_needs(), StageNeeds { performers: 5, stage_m2: 26, inputs: 17 });
+    }
+
+    #[test]
+    fn acts_need_someone_on_stage() {
+        let mut booker = Booker::new();
+        let fee = Money::from_major(100, Currency::Usd);
+        assert_eq!(booker.add_act(Act::new("Nobody", Band(0), &[Genre::Rock], fee)), Err(GigError::NoPerformers("Nobody".to_string())));
+        assert_eq!(booker.acts_for(&[Genre::Rock]).len(), 0);
+        booker.add_act(Act::new("Duo", Band(2), &[Genre::Rock], fee)).unwrap();
+        assert_eq!(booker.acts_for(&[Genre::Rock]).len(), 1);
+    }
+
+    #[test]
+    fn setlist_length_overflow_is_an_error() {
+        let mut setlist = Setlist::new(Duration::new(0, 1, 0));
+        setlist.add("One", "3m").unwrap();
+        setlist.add("Two", "4m30s").unwrap();
+        assert_eq!(setlist.length(), Ok(Duration::new(0, 8, 30)));
+
+        let mut endless = Setlist::new(Duration::new(u32::MAX, 0, 0));
+        for title in ["One", "Two", "Three"] { // Two gaps of u32::MAX hours
+            endless.add(title, "3m").unwrap();
+        }
+        assert_eq!(endless.length(), Err(GigError::SetlistLength(DurationError::Overflow)));
+
+        let mut booker = Booker::new();
+        booker.add_act(Act::new("Duo", Band(2), &[Genre::Rock], Money::from_major(100, Currency::Usd))).unwrap();
+        booker.add_venue(Venue::new("Hall", 100, 50, 20)).unwrap();
+        let night = Date::parse("2024-11-08").unwrap();
+        assert_eq!(
+            booker.book("Duo", "Hall", night, "8:00 PM", "9:00 PM", endless),
+            Err(GigError::SetlistLength(DurationError::Overflow))
+        );
+        assert!(booker.gigs().is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+// Actual vs target reporting for anything that implements Investment<T>. Time (Investment<u32> minutes) and money
+// (Investment<f64> dollars) go through the same code, only the formatter for the amounts differs.
+use std::fmt::{Display, Formatter};
+
+use crate::Investment;
+
+pub struct Goal<'a, T> {
+    pub label: String,
+    pub investment: &'a dyn Investment<T>,
+    pub target: T,
+}
+
+impl<'a, T> Goal<'a, T> {
+    pub fn new(label: &str, investment: &'a dyn Investment<T>, target: T) -> Self {
+        Self { label: label.to_string(), investment, target }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalLine {
+    pub label: String,
+    pub actual: f64,
+    pub target: f64,
+}
+
+impl GoalLine {
+    // A zero target counts as met
+    pub fn progress(&self) -> f64 {
+        if self.target <= 0.0 { 1.0 } else { self.actual / self.target }
+    }
+
+    pub fn is_met(&self) -> bool {
+        self.actual >= self.target
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GoalReport {
+    pub title: String,
+    pub lines: Vec<GoalLine>,
+    format: fn(f64) -> String, // Renders an amount, e.g. dollars or hours and minutes
+}
+
+pub fn report<T: Copy + Into<f64>>(title: &str, goals: &[Goal<'_, T>], format: fn(f64) -> String) -> GoalReport {
+    let lines = goals
+        .iter()
+        .map(|goal| GoalLine { label: goal.label.clone(), actual: goal.investment.get_amount().into(), target: goal.target.into() })
+        .collect();
+    GoalReport { title: title.to_string(), lines, format }
+}
+
+impl GoalReport {
+    pub fn goals_met(&self) -> usize {
+        self.lines.iter().filter(|line| line.is_met()).count()
+    }
+}
+
+pub fn dollars(amount: f64) -> String {
+    format!("${:.2}", amount)
+}
+
+const BAR_WIDTH: usize = 20;
+
+impl Display for GoalReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "** {} **", self.title)?;
+        for line in &self.lines {
+            let filled = ((line.progress() * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+            writeln!(
+                f,
+                "{:<16} {:>12} / {:<12} [{}{}] {:>4.0}%{}",
+                line.label,
+                (self.format)(line.actual),
+                (self.format)(line.target),
+                "#".repeat(filled),
+                " ".repeat(BAR_WIDTH - filled),
+                line.progress() * 100.0,
+                if line.is_met() { "" } else { " (behind)" }
+            )?;
+        }
+        write!(f, "{} of {} goals met", self.goals_met(), self.lines.len())
+    }
+}
@@ -0,0 +1,247 @@
+// Guards that clean up after themselves in Drop, the way the Drop comments in main.rs describe. Drop runs whenever the
+// guard goes out of scope: at the end of a block, on an early return through `?`, and while a panic unwinds the stack.
+// So the file gets deleted, the lock released or the changes rolled back no matter how the function is left.
+use std::fmt::{Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+#[derive(Debug)]
+pub enum GuardError {
+    Io(io::Error),
+    Locked(PathBuf), // Someone else already holds the lock
+}
+
+impl Display for GuardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuardError::Io(error) => write!(f, "{}", error),
+            GuardError::Locked(path) => write!(f, "{} is already locked", path.display()),
+        }
+    }
+}
+
+impl From<io::Error> for GuardError {
+    fn from(error: io::Error) -> Self {
+        GuardError::Io(error)
+    }
+}
+
+// Deleting something that's already gone is fine. Drop can't return an error, so it ignores this one, and the guards
+// have a method that cleans up early for callers who need to know it worked.
+fn remove(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+static NEXT_TEMP_FILE: AtomicU32 = AtomicU32::new(0);
+
+// A file in the system temp directory that is deleted when the guard is dropped, unless it's kept
+#[derive(Debug)]
+pub struct TempFile {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempFile {
+    pub fn create(prefix: &str, contents: &str) -> Result<Self, GuardError> {
+        let name = format!("{}-{}-{}.tmp", prefix, process::id(), NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed));
+        let path = std::env::temp_dir().join(name);
+        let mut file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+        let guard = Self { path, keep: false }; // From here on, a failed write still removes the file
+        file.write_all(contents.as_bytes())?;
+        Ok(guard)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Hands the file over to the caller, who becomes responsible for it
+    pub fn keep(mut self) -> PathBuf {
+        self.keep = true;
+        self.path.clone()
+    }
+
+    // Deletes the file now instead of in Drop, and says if that failed
+    pub fn close(mut self) -> Result<(), GuardError> {
+        self.keep = true;
+        Ok(remove(&self.path)?)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = remove(&self.path);
+        }
+    }
+}
+
+// Holds a lock file for as long as the guard lives. Creating the file fails if it already exists, so only one
+// guard can hold a given lock at a time.
+#[derive(Debug)]
+pub struct LockFile {
+    path: PathBuf,
+    released: bool,
+}
+
+impl LockFile {
+    pub fn acquire<P: AsRef<Path>>(path: P) -> Result<Self, GuardError> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => return Err(GuardError::Locked(path)),
+            Err(error) => return Err(error.into()),
+        };
+        let guard = Self { path, released: false };
+        writeln!(file, "{}", process::id())?; // Who holds it, for anyone looking at a stale lock
+        Ok(guard)
+    }
+
+    // Releases the lock now instead of in Drop, and says if the lock file couldn't be removed
+    pub fn release(mut self) -> Result<(), GuardError> {
+        self.released = true;
+        Ok(remove(&self.path)?)
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        if !self.released {
+            let _ = remove(&self.path);
+        }
+    }
+}
+
+// Changes made through the guard go straight to the value, but are undone when the guard is dropped without commit()
+pub struct Transaction<'a, T: Clone> {
+    value: &'a mut T,
+    snapshot: Option<T>, // None once committed
+}
+
+impl<'a, T: Clone> Transaction<'a, T> {
+    pub fn begin(value: &'a mut T) -> Self {
+        let snapshot = Some(value.clone());
+        Self { value, snapshot }
+    }
+
+    pub fn commit(mut self) {
+        self.snapshot = None;
+    }
+}
+
+impl<T: Clone> Deref for Transaction<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T: Clone> DerefMut for Transaction<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value
+    }
+}
+
+impl<T: Clone> Drop for Transaction<'_, T> {
+    fn drop(&mut self) {
+        if let Some(snapshot) = self.snapshot.take() {
+            *self.value = snapshot;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn lock_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{}.lock", name, process::id()))
+    }
+
+    // Creates both guards, then fails with `?` before it's done
+    fn half_done(seen: &mut Vec<PathBuf>) -> Result<(), GuardError> {
+        let file = TempFile::create("guards-test", "draft")?;
+        let lock = LockFile::acquire(lock_path("guards-test-return"))?;
+        seen.push(file.path().to_path_buf());
+        seen.push(lock.path.clone());
+        Err(io::Error::other("failed partway"))?;
+        Ok(())
+    }
+
+    #[test]
+    fn early_return_cleans_up() {
+        let mut seen = Vec::new();
+        assert!(half_done(&mut seen).is_err());
+        assert_eq!(seen.len(), 2);
+        assert!(seen.iter().all(|path| !path.exists()));
+    }
+
+    #[test]
+    fn panic_cleans_up() {
+        let mut seen = Vec::new();
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            let file = TempFile::create("guards-test", "draft").unwrap();
+            let lock = LockFile::acquire(lock_path("guards-test-panic")).unwrap();
+            seen.push(file.path().to_path_buf());
+            seen.push(lock.path.clone());
+            panic!("failed partway");
+        }));
+        assert!(outcome.is_err());
+        assert_eq!(seen.len(), 2);
+        assert!(seen.iter().all(|path| !path.exists()));
+        assert!(LockFile::acquire(lock_path("guards-test-panic")).is_ok());
+    }
+
+    #[test]
+    fn kept_and_closed_files() {
+        let kept = TempFile::create("guards-test", "report").unwrap().keep();
+        assert_eq!(fs::read_to_string(&kept).unwrap(), "report");
+        fs::remove_file(&kept).unwrap();
+
+        let file = TempFile::create("guards-test", "notes").unwrap();
+        let path = file.path().to_path_buf();
+        fs::remove_file(&path).unwrap(); // Already gone is fine
+        assert!(file.close().is_ok());
+    }
+
+    #[test]
+    fn lock_is_exclusive_until_released() {
+        let path = lock_path("guards-test-release");
+        let lock = LockFile::acquire(&path).unwrap();
+        assert!(matches!(LockFile::acquire(&path), Err(GuardError::Locked(_))));
+        lock.release().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn transaction_rolls_back_unless_committed() {
+        let mut ledger = vec![1, 2];
+        {
+            let mut transaction = Transaction::begin(&mut ledger);
+            transaction.push(3);
+            assert_eq!(*transaction, [1, 2, 3]);
+        }
+        assert_eq!(ledger, [1, 2]);
+
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut transaction = Transaction::begin(&mut ledger);
+            transaction.clear();
+            panic!("failed partway");
+        }));
+        assert!(outcome.is_err());
+        assert_eq!(ledger, [1, 2]);
+
+        let mut transaction = Transaction::begin(&mut ledger);
+        transaction.push(4);
+        transaction.commit();
+        assert_eq!(ledger, [1, 2, 4]);
+    }
+}
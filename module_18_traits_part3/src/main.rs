@@ -111,23 +111,41 @@
  */
 
 
+mod bill;
+mod clock;
+mod duration;
+mod gigs;
+mod goals;
+mod guards;
+mod money;
+mod numeric;
+mod offers;
+mod portfolio;
+mod produce;
+mod scheduler;
+mod tax;
+mod time_budget;
+mod trips;
+
 use std::fmt::{Debug, Display, Formatter, Result};
 use std::ops::Drop; // May be in the prelude but we'll do this anyway
 use std::clone::Clone; // This is in the rust prelude but we're including it anyway
 use std::cmp::Ordering; // cmp is the "compare" module
 use std::ops::Add; // ops is the "operations" library
 
+use money::{Currency, Money, Rounding};
+
 fn add_two_numbers<T: Add<Output = T>>(a: T, b: T) -> T {
     // This function is operating on a Generic that implements the Add trait, where the Add trait returns the generic type T as it's associated output type
     return a+b;  // This will not compile because the compiler cannot guarantee that a and b are types that can be added together, unless we add a trait constraint for the generic to implement the Add trait! On it's own, the Add trait is insufficient though, because the definition of the trait indicates that it returns a Self::Output type - and so it needs to know what the return type will be!
 }
 #[derive(Debug)]
 struct Lunch {
-    cost: f64,
+    cost: Money, // Exact minor units, so adding up lunches never picks up f64 rounding errors
 }
 
 impl Add for Lunch {
-    type Output = f64; //Our associated type
+    type Output = Money; //Our associated type
     // An alternative implementation if we try to change the output to be a lunch struct with the new total cost instead
     // type Output = Lunch;
     // fn add(self, rhs: Self) -> Self {
@@ -135,7 +153,7 @@ impl Add for Lunch {
     //         cost: self.cost + rhs.cost
     //     }
     // }
-    fn add(self, rhs: Self) -> Self::Output { // Doing things this ways sets the Add implementation to return a f64 if we add two lunch structs together
+    fn add(self, rhs: Self) -> Self::Output { // Doing things this ways sets the Add implementation to return Money if we add two lunch structs together
         self.cost + rhs.cost
     }
 }
@@ -240,7 +258,7 @@ impl PartialEq<BusTrip> for Flight { // This is the definition of partial eq bet
 }
 
 
-# [derive(Debug, Clone)] // Here, "Duration" is implementing the "Clone" supertrait
+# [derive(Debug, Clone)] // Here, "Duration" is implementing the "Clone" supertrait. PartialEq and Ord are in duration.rs
 struct Duration {
     hours: u32,
     minutes: u32,
@@ -251,11 +269,9 @@ impl Copy for Duration {}
 
 impl Duration {
     fn new(hours: u32, minutes: u32, seconds: u32) -> Self { // This is an example where the clone gives back a new data stored on the stack, because it's sub-components are stack data types
-        Self {
-            hours: hours,
-            minutes: minutes,
-            seconds: seconds
-        }
+        // Minutes and seconds past 59 carry over (see duration.rs). When carrying would push the hours past u32::MAX the
+        // fields are kept as given; equality and ordering still go by the total, and try_new reports the overflow instead
+        Self::try_new(hours, minutes, seconds).unwrap_or(Self { hours, minutes, seconds })
     }
 }
 
@@ -278,31 +294,34 @@ impl Appointment {
         return Self { doctor: doctor.to_string(), start_time: start_time.to_string(), end_time: end_time.to_string() };
     }
 
-    fn change_start_time(&mut self, new_time: &str) {
+    // Only if the new time can be read and is still before the end time
+    fn change_start_time(&mut self, new_time: &str) -> std::result::Result<(), scheduler::ScheduleError> {
+        let (start, end) = (clock::Clock::parse(new_time)?, clock::Clock::parse(&self.end_time)?);
+        if start >= end {
+            return Err(scheduler::ScheduleError::EndsBeforeStart { start, end });
+        }
         self.start_time = new_time.to_string();
+        Ok(())
     }
 }
 
-enum AppleType {
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum AppleType { // Names, shelf life and prices live in one table, AppleType::info in produce.rs
     RedDelicious,
     GrannySmith,
+    Honeycrisp,
+    Fuji,
 }
 
 impl Display for AppleType {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        match self {
-            AppleType::RedDelicious => write!(f, "Red Delicious"),
-            AppleType::GrannySmith => write!(f, "Granny Smith"),
-        }
+        f.pad(self.info().name) // pad so widths like {:<14} line up
     }
 }
 
 impl Debug for AppleType {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        match self {
-            AppleType::RedDelicious => write!(f, "AppleType::Red Delicious"),
-            AppleType::GrannySmith => write!(f, "AppleType::Granny Smith"),
-        }
+        write!(f, "AppleType::{}", self.info().name)
     }
 }
 
@@ -377,6 +396,20 @@ impl Taxable for Bonus {
     const TAX_RATE: f64 = 0.5; // Override the default TAX_RATE
 }
 
+#[derive(Debug)]
+struct Dividend { // Investment income that isn't Taxable, so nothing is withheld from it
+    amount: f64
+}
+
+impl Investment<f64> for Dividend {
+    fn double_amount(&mut self) {
+        self.amount *= 2.0;
+    }
+    fn get_amount(&self) -> f64 {
+        self.amount
+    }
+}
+
 # [derive(Debug)]
 struct QualityTime {
     amount: u32
@@ -434,7 +467,10 @@ fn main() {
     println!("{:#?}", morning_appointment);
     println!("{:#?}", cloned_appointment);
 
-    cloned_appointment.change_start_time("8:30");
+    cloned_appointment.change_start_time("8:30").unwrap();
+    if let Err(error) = cloned_appointment.change_start_time("10:30 AM") {
+        println!("{}", error);
+    }
     println!("After cloning and changing the times . . . ");
     println!("{:#?}", morning_appointment);
     println!("{:#?}", cloned_appointment);
@@ -490,12 +526,413 @@ fn main() {
     println!("{}", long_commute_job < short_commute_job); // You can also use methods --> lt, le, gt, ge
 
     println!("Associated Types");
-    let lunch1 = Lunch{cost: 10.0};
-    let lunch2 = Lunch{cost: 5.0};
+    let lunch1 = Lunch{cost: Money::from_minor(1010, Currency::Usd)};
+    let lunch2 = Lunch{cost: Money::from_minor(520, Currency::Usd)};
     println!("{}", lunch1 + lunch2); // this now works for our custom type
 
     println!("{}", add_two_numbers(1.5, 2.4));
     println!("{}", add_two_numbers(1, 2));
 
+    println!("Progressive Tax Brackets");
+    let brackets = concat!(env!("CARGO_MANIFEST_DIR"), "/brackets.cfg");
+    match tax::TaxTable::load(brackets) {
+        Ok(table) => {
+            let mut single = tax::TaxReturn::new(tax::FilingStatus::Single);
+            single.add_taxable("Salary", &income);
+            single.add_taxable("Bonus", &bonus);
+            single.add_investment("Dividends", &Dividend { amount: 2500.00 });
+            match single.compute(&table) {
+                Ok(summary) => println!("{}", summary),
+                Err(error) => println!("{}", error),
+            }
+
+            let mut couple = tax::TaxReturn::new(tax::FilingStatus::MarriedJoint);
+            couple.add_taxable("Salary", &income);
+            couple.add_taxable("Salary", &Income { amount: 95000.00 });
+            couple.itemize("Mortgage interest", 24000.00).unwrap();
+            couple.itemize("Charitable giving", 8000.00).unwrap(); // Together these beat the standard deduction
+            if let Err(error) = couple.itemize("Medical expenses", -500.00) {
+                println!("{}", error);
+            }
+            match couple.compute(&table) {
+                Ok(summary) => println!("{}", summary),
+                Err(error) => println!("{}", error),
+            }
+        }
+        Err(error) => println!("{}", error),
+    }
+    if let Err(error) = tax::TaxTable::parse("single | bracket | 11600 | 12") {
+        println!("{}", error);
+    }
+
+    println!("Portfolio Projections");
+    use portfolio::{Account, Frequency, Portfolio};
+    let mut retirement = Portfolio::new();
+    retirement.add(Account::new("Index fund", 50000.00, 0.07, Frequency::Monthly).and_then(|a| a.with_contribution(6000.00)).and_then(|a| a.with_volatility(0.15)).unwrap());
+    retirement.add(Account::new("Savings", 10000.00, 0.04, Frequency::Daily).and_then(|a| a.with_volatility(0.005)).unwrap());
+    retirement.add(Account::new("Bonds", 20000.00, 0.05, Frequency::Annually).and_then(|a| a.with_withdrawal(2000.00)).and_then(|a| a.with_volatility(0.06)).unwrap());
+    retirement.add(Account::new("CD", 5000.00, 0.045, Frequency::Quarterly).unwrap());
+    if let Err(error) = Account::new("Mattress", 100.00, 0.0, Frequency::Annually).and_then(|a| a.with_withdrawal(f64::NAN)) {
+        println!("{}", error);
+    }
+    if let Some(savings) = retirement.account_mut("Savings") {
+        savings.contribute(bonus.get_amount()).unwrap(); // The bonus goes straight into savings
+        if let Err(error) = savings.withdraw(1_000_000.00) {
+            println!("{}", error);
+        }
+    }
+    println!("Starting total: ${:.2}", retirement.total());
+    print!("{}", retirement.project(10));
+    let simulation = retirement.monte_carlo(10, 1000, 42);
+    println!("{}", simulation);
+    println!("Same seed, same results --> {}", simulation == retirement.monte_carlo(10, 1000, 42));
+    println!("Different seed, same results --> {}", simulation == retirement.monte_carlo(10, 1000, 7));
+
+    println!("Time Budgeting");
+    let mut budget = time_budget::TimeBudget::new();
+    budget.set_goal("Family", 10 * 60);
+    budget.set_goal("Exercise", 3 * 60);
+    budget.set_goal("Reading", 2 * 60);
+    budget.log(1, "Family", "Dinner", Some("Mom"), 90).unwrap();
+    budget.log(1, "Family", "Board games", Some("Sam"), 120).unwrap();
+    budget.log(1, "Family", "Hike", Some("Sam"), weekend.get_amount() * 12).unwrap(); // 20 x 12 = 240 minutes
+    budget.log(1, "Exercise", "Running", None, 200).unwrap();
+    budget.log(1, "Reading", "Novel", None, 45).unwrap();
+    budget.log(2, "Family", "Dinner", Some("Mom"), 60).unwrap();
+    if let Err(error) = budget.log(1, "Reading", "Skimming", None, 0) {
+        println!("{}", error);
+    }
+    println!("{}", budget.weekly_report(1));
+    for (person, time) in budget.people(1) {
+        println!("  With {} --> {}", person, clock::hours_minutes(time.get_amount() as f64));
+    }
+    println!("{}", budget.weekly_report(2));
+
+    let savings_goals: Vec<goals::Goal<'_, f64>> = vec![
+        goals::Goal::new("Salary", &income, 150000.00),
+        goals::Goal::new("Bonus", &bonus, 15000.00),
+    ];
+    println!("{}", goals::report("Money goals", &savings_goals, goals::dollars)); // Same report, now over Investment<f64>
+
+
 
+    println!("Exact Money");
+    println!("0.1 + 0.2 in f64 --> {}", 0.1 + 0.2);
+    println!("$0.10 + $0.20 in Money --> {}", Money::from_minor(10, Currency::Usd) + Money::from_minor(20, Currency::Usd));
+    let bill = Money::from_minor(10000, Currency::Usd);
+    for rounding in [Rounding::Floor, Rounding::Ceiling, Rounding::HalfUp, Rounding::HalfEven] {
+        println!("A third of {} rounded {:?} --> {}", bill, rounding, bill.mul_ratio(1, 3, rounding).unwrap());
+    }
+    let half_cent = Money::from_minor(5, Currency::Usd); // Tenths of this land exactly on half a cent
+    println!("Half a cent: HalfUp --> {}, HalfEven --> {}", half_cent.mul_ratio(1, 10, Rounding::HalfUp).unwrap(), half_cent.mul_ratio(1, 10, Rounding::HalfEven).unwrap());
+    let parts = bill.split(3).unwrap();
+    println!("{} split three ways --> {:?}", bill, parts.iter().map(|part| part.to_string()).collect::<Vec<_>>());
+    println!("Parts add back up --> {}", parts.iter().fold(Money::zero(Currency::Usd), |total, part| total + *part) == bill);
+    let shares = Money::from_major(1000, Currency::Jpy).allocate(&[50, 30, 20]).unwrap();
+    println!("¥1000 at 50/30/20 --> {:?}", shares.iter().map(|share| share.to_string()).collect::<Vec<_>>());
+    if let Err(error) = Money::from_major(5, Currency::Eur).checked_add(Money::from_major(5, Currency::Gbp)) {
+        println!("{}", error);
+    }
+    if let Err(error) = Money::checked_from_major(i64::MAX / 10, Currency::Usd) {
+        println!("{}", error);
+    }
+    println!("Tax bill as Money --> {}", Money::from_f64(income.tax_bill(), Currency::Usd).unwrap());
+
+    println!("Splitting the Check");
+    let usd = |cents| Lunch { cost: Money::from_minor(cents, Currency::Usd) };
+    let mut check = bill::Check::new(Currency::Usd, &["Ana", "Ben", "Cy"]);
+    check.set_tax(875); // 8.75%
+    check.set_tip(18);
+    check.add("Burger", usd(1499), &["Ana"]).unwrap();
+    check.add("Salad", usd(1125), &["Ben"]).unwrap();
+    check.add("Ramen", usd(1650), &["Cy"]).unwrap();
+    check.add("Nachos", usd(1000), &["Ana", "Ben", "Cy"]).unwrap(); // $10.00 doesn't split evenly three ways
+    if let Err(error) = check.add("Dessert", usd(800), &["Dee"]) {
+        println!("{}", error);
+    }
+    if let Err(error) = check.add("Espresso", Lunch { cost: Money::from_minor(300, Currency::Eur) }, &["Ben"]) {
+        println!("{}", error);
+    }
+    println!("{}", check);
+    for split in [bill::Split::Even, bill::Split::ByItem, bill::Split::ByShares(vec![2, 1, 1])] {
+        let bill = check.split(&split).unwrap();
+        println!("{}", bill);
+        println!("Adds up to the check --> {}", bill.is_balanced());
+    }
+    if let Err(error) = check.split(&bill::Split::ByShares(vec![1, 1])) {
+        println!("{}", error);
+    }
+
+    println!("Comparing Job Offers");
+    use offers::{Benefits, Offer, Weights};
+    let job_offers = vec![
+        Offer::new("Downtown Bank", long_commute_job, Benefits { health_insurance: 8000, retirement_match: 0.03, pto_days: 15, remote_days_per_week: 0 }),
+        Offer::new("Local Startup", short_commute_job, Benefits { health_insurance: 5000, retirement_match: 0.0, pto_days: 20, remote_days_per_week: 3 }),
+        Offer::new("Remote Co", Job { salary: 90000, commute_time: 0 }, Benefits { health_insurance: 6000, retirement_match: 0.05, pto_days: 25, remote_days_per_week: 5 }),
+    ];
+    for evaluation in offers::rank(&job_offers, &Weights::default()).unwrap() {
+        print!("{}", evaluation);
+    }
+    let money_first = Weights { salary: 0.8, commute: 0.0, benefits: 0.2, time_off: 0.0 };
+    let ranking = offers::rank(&job_offers, &money_first).unwrap();
+    println!("Weighting only money, {} comes out on top", ranking[0].company);
+    if let Err(error) = offers::rank(&job_offers, &Weights { salary: -1.0, ..money_first }) {
+        println!("{}", error);
+    }
+
+    println!("Trip Planning");
+    use trips::{Preference, Timetable};
+    let mut timetable = Timetable::new(trips::ConnectionTimes::default());
+    timetable.add_flight(&flight1, 420, 650).unwrap(); // New York to London, 10:00 AM
+    timetable.add_flight(&Flight::new("New York", "Boston", "7:00 AM"), 75, 180).unwrap();
+    timetable.add_bus(&BusTrip::new("New York", "Boston", "6:00 AM"), 270, 35).unwrap();
+    timetable.add_flight(&Flight::new("Boston", "London", "9:00 AM"), 390, 520).unwrap();
+    timetable.add_flight(&Flight::new("Boston", "London", "11:30 AM"), 390, 410).unwrap();
+    timetable.add_flight(&Flight::new("Boston", "London", "6:00 PM"), 390, 300).unwrap();
+    timetable.add_bus(&BusTrip::new("Boston", "Portland", "11:00 AM"), 120, 25).unwrap();
+    timetable.add_flight(&Flight::new("Portland", "London", "3:00 PM"), 420, 380).unwrap();
+    if let Err(error) = timetable.add_bus(&BusTrip::new("Boston", "New York", "25:00"), 270, 35) {
+        println!("{}", error);
+    }
+    let leave_at = clock::Clock::parse("5:30 am").unwrap();
+    for preference in [Preference::Fastest, Preference::Cheapest, Preference::FewestTransfers] {
+        match timetable.plan("New York", "London", leave_at, preference) {
+            Ok(itinerary) => print!("{:?}: {}", preference, itinerary),
+            Err(error) => println!("{}", error),
+        }
+    }
+    println!("{} itineraries worth considering", timetable.itineraries("New York", "London", leave_at).len());
+    if let Err(error) = timetable.plan("London", "New York", leave_at, Preference::Fastest) {
+        println!("{}", error);
+    }
+
+    println!("Duration Arithmetic");
+    let ninety_minutes = Duration::new(0, 90, 0); // Normalized to 1h30m
+    println!("{:?} --> {}", ninety_minutes, ninety_minutes);
+    println!("{} + {} = {}", one_hour, ninety_minutes, one_hour + ninety_minutes);
+    println!("{} - {} = {}", ninety_minutes, one_hour, ninety_minutes - one_hour);
+    println!("{} x 3 = {}", ninety_minutes, ninety_minutes * 3);
+    println!("{} == 1h30m --> {}", ninety_minutes, ninety_minutes == Duration::new(1, 30, 0));
+    println!("{} > {} --> {}", ninety_minutes, one_hour, ninety_minutes > one_hour);
+    for text in ["1h30m", "01:30:00", "PT1H30M", "P1DT2H", "45s", "2:05", "1m30h", "PT", "99:75:00"] {
+        match text.parse::<Duration>() {
+            Ok(duration) => println!("{:>9} --> {} / {} / {}", text, duration, duration.to_clock_string(), duration.to_iso8601()),
+            Err(error) => println!("{}", error),
+        }
+    }
+    if let Err(error) = one_hour.checked_sub(ninety_minutes) {
+        println!("{}", error);
+    }
+    println!("Saturating instead --> {}", one_hour.saturating_sub(ninety_minutes));
+    if let Err(error) = Duration::new(u32::MAX, 0, 0).checked_add(one_hour) {
+        println!("{}", error);
+    }
+    if let Err(error) = ninety_minutes.checked_mul(u32::MAX) {
+        println!("{}", error);
+    }
+
+    println!("Appointment Scheduling");
+    let mut schedule = scheduler::Scheduler::new();
+    schedule.set_hours("Dr. Alashwal", "8:00 AM", "4:00 PM").unwrap();
+    let first = schedule.book(&morning_appointment).unwrap();
+    schedule.book(&Appointment::new("Dr. Alashwal", "10:00 AM", "10:30 AM")).unwrap(); // Starts the minute the first one ends
+    schedule.book(&Appointment::new("Dr. Alashwal", "11:00 AM", "12:00 PM")).unwrap();
+    for (start, end) in [("9:30 AM", "10:15 AM"), ("2:00 PM", "1:00 PM"), ("3:30 PM", "5:00 PM"), ("noon", "1:00 PM")] {
+        if let Err(error) = schedule.book(&Appointment::new("Dr. Alashwal", start, end)) {
+            println!("{} to {}: {}", start, end, error);
+        }
+    }
+    let forty_five_minutes = "45m".parse::<Duration>().unwrap();
+    if let Some((start, end)) = schedule.next_free_slot("Dr. Alashwal", forty_five_minutes, "8:00 AM").unwrap() {
+        println!("Next {} slot after 8:00 AM --> {} to {}", forty_five_minutes, start, end);
+    }
+    if let Some((start, end)) = schedule.next_free_slot("Dr. Alashwal", forty_five_minutes, "9:00 AM").unwrap() {
+        println!("Next {} slot after 9:00 AM --> {} to {}", forty_five_minutes, start, end);
+    }
+    if let Err(error) = schedule.reschedule("Dr. Alashwal", first, "11:30 AM") {
+        println!("Rescheduling {}: {}", first, error);
+    }
+    let moved = schedule.reschedule("Dr. Alashwal", first, "1:00 PM").unwrap();
+    println!("Moved {} to {} - {}", moved.id, moved.start, moved.end);
+    if let Err(error) = schedule.set_hours("Dr. Alashwal", "9:00 AM", "12:30 PM") { // The 1:00 PM booking is still there
+        println!("{}", error);
+    }
+    schedule.cancel("Dr. Alashwal", first).unwrap();
+    println!("Next 5h slot --> {:?}", schedule.next_free_slot("Dr. Alashwal", Duration::new(5, 0, 0), "8:00 AM").unwrap());
+    for booking in schedule.bookings("Dr. Alashwal") {
+        println!("  {} {} - {}", booking.id, booking.start, booking.end);
+    }
+
+    println!("Cleanup Guards");
+    use guards::{GuardError, LockFile, TempFile, Transaction};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::path::PathBuf;
+
+    // Writes a draft report, then bails out with `?` before it's finished. The draft is still deleted.
+    fn draft_report(seen: &mut Option<PathBuf>, finish: bool) -> std::result::Result<PathBuf, GuardError> {
+        let draft = TempFile::create("report", "Quarterly numbers")?;
+        *seen = Some(draft.path().to_path_buf());
+        let _lock = LockFile::acquire(draft.path().with_extension("lock"))?;
+        if !finish {
+            return Err(GuardError::Io(std::io::Error::other("the numbers didn't add up"))); // Any failure partway through
+        }
+        Ok(draft.keep())
+    }
+
+    let mut draft = None;
+    println!("Early return --> {}", draft_report(&mut draft, false).unwrap_err());
+    let draft = draft.unwrap();
+    println!("Draft still on disk --> {}, lock still on disk --> {}", draft.exists(), draft.with_extension("lock").exists());
+    let mut kept = None;
+    let report = draft_report(&mut kept, true).unwrap();
+    println!("Kept report on disk --> {}, lock still on disk --> {}", report.exists(), report.with_extension("lock").exists());
+    std::fs::remove_file(&report).unwrap();
+    let notes = TempFile::create("notes", "Call the bank").unwrap();
+    let notes_path = notes.path().to_path_buf();
+    notes.close().unwrap(); // Same as dropping it, except a failed delete comes back as an error
+    println!("Closed temp file on disk --> {}", notes_path.exists());
+
+    let lock_path = std::env::temp_dir().join(format!("ledger-{}.lock", std::process::id()));
+    let held = LockFile::acquire(&lock_path).unwrap();
+    if let Err(error) = LockFile::acquire(&lock_path) {
+        println!("{}", error);
+    }
+    held.release().unwrap();
+    println!("Lock free again --> {}", LockFile::acquire(&lock_path).is_ok()); // That guard drops right away too
+
+    let mut ledger = vec![String::from("Opening balance")];
+    {
+        let mut transaction = Transaction::begin(&mut ledger);
+        transaction.push(String::from("Lunch"));
+        transaction.push(String::from("Typo'd entry"));
+    } // Never committed
+    println!("Rolled back --> {:?}", ledger);
+    let mut transaction = Transaction::begin(&mut ledger);
+    transaction.push(String::from("Lunch"));
+    transaction.commit();
+    println!("Committed --> {:?}", ledger);
+
+    let quiet = panic::take_hook(); // Keep the expected panics below from printing a backtrace message
+    panic::set_hook(Box::new(|_| {}));
+    let mut scratch = None;
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+        let file = TempFile::create("scratch", "half written").unwrap();
+        scratch = Some(file.path().to_path_buf());
+        let _lock = LockFile::acquire(&lock_path).unwrap();
+        let mut transaction = Transaction::begin(&mut ledger);
+        transaction.push(String::from("Never saved"));
+        panic!("Something went wrong mid-update");
+    }));
+    panic::set_hook(quiet);
+    let scratch = scratch.unwrap();
+    println!("Panicked --> {}", outcome.is_err());
+    println!("After the panic: temp file on disk --> {}, locked --> {}, ledger --> {:?}", scratch.exists(), lock_path.exists(), ledger);
+
+    println!("Fruit Stand");
+    use produce::{Date, FruitStand, Grade};
+    let date = |text: &str| Date::parse(text).unwrap();
+    let mut stand = FruitStand::new();
+    stand.receive(AppleType::Honeycrisp, 4000, date("2024-08-20"), Grade::ExtraFancy).unwrap();
+    stand.receive(AppleType::RedDelicious, 2500, date("2024-08-01"), Grade::Utility).unwrap();
+    stand.receive(AppleType::Honeycrisp, 6000, date("2024-09-10"), Grade::Fancy).unwrap();
+    stand.receive(AppleType::Honeycrisp, 1500, date("2024-07-15"), Grade::Fancy).unwrap(); // Already past its shelf life by October
+    stand.receive(AppleType::GrannySmith, 5000, date("2024-09-01"), Grade::Fancy).unwrap();
+    stand.receive(AppleType::Fuji, 3000, date("2024-09-25"), Grade::ExtraFancy).unwrap();
+    for variety in [AppleType::RedDelicious, AppleType::GrannySmith, AppleType::Honeycrisp, AppleType::Fuji] {
+        let info = variety.info();
+        println!("{}: keeps {} days, {} per kg", variety, info.shelf_life_days, info.price_per_kg);
+    }
+
+    let today = date("2024-10-01");
+    println!("{}", stand.sell(AppleType::Honeycrisp, 5500, today).unwrap()); // Finishes the older fresh batch first
+    if let Err(error) = stand.sell(AppleType::Honeycrisp, 10_000, today) {
+        println!("{}", error);
+    }
+    println!("{}", stand.sell(AppleType::Fuji, 750, today).unwrap());
+    println!("{}", stand.spoilage_report(today, 21).unwrap());
+    for batch in stand.discard_expired(today) {
+        println!("Discarded {} ({}g of {})", batch.id, batch.grams, batch.variety);
+    }
+    println!("Fresh Honeycrisp left --> {}g", stand.fresh_grams(AppleType::Honeycrisp, today));
+    if Date::parse("2024-02-30").is_none() {
+        println!("2024-02-30 is not a date");
+    }
+
+    println!("Gig Booking");
+    use gigs::{Act, Booker, Genre, Setlist, Venue};
+    let mut booker = Booker::new();
+    booker.add_act(Act::new("Holly", holly, &[Genre::Folk, Genre::Country], Money::from_major(800, Currency::Usd))).unwrap();
+    booker.add_act(Act::new("One Direction", one_direction, &[Genre::Pop], Money::from_major(250_000, Currency::Usd))).unwrap();
+    booker.add_act(Act::new("Backstreet Boys", backstreet_boys, &[Genre::Pop, Genre::Rock], Money::from_major(400_000, Currency::Usd))).unwrap();
+    if let Err(error) = booker.add_act(Act::new("Milli Vanilli", Band(0), &[Genre::Pop], Money::from_major(5_000, Currency::Usd))) {
+        println!("{}", error);
+    }
+    booker.add_venue(Venue::new("The Basement", 120, 20, 16)).unwrap();
+    booker.add_venue(Venue::new("Madison Square Garden", 19_500, 200, 96)).unwrap();
+    for (name, members) in [("Holly", 1), ("One Direction", 5), ("Backstreet Boys", 32)] {
+        let needs = if members == 1 { SingerSongWriter(name.to_string()) } else { Band(members) }.stage_needs();
+        println!("{} --> {}m2 of stage, {} inputs", name, needs.stage_m2, needs.inputs);
+    }
+
+    let mut folk_set = Setlist::new(Duration::new(0, 1, 0));
+    for (title, length) in [("Open Road", "4m10s"), ("Cedar Creek", "3m45s"), ("Last Call", "5m")] {
+        folk_set.add(title, length).unwrap();
+    }
+    if let Err(error) = folk_set.add("Encore", "a while") {
+        println!("{}", error);
+    }
+    let mut pop_set = Setlist::new(Duration::new(0, 2, 0));
+    for number in 1..=20 {
+        pop_set.add(&format!("Hit #{}", number), "3m30s").unwrap();
+    }
+    let opening_night = Date::parse("2024-11-08").unwrap();
+    let next_night = opening_night.add_days(1);
+    let holly_gig = booker.book("Holly", "The Basement", opening_night, "8:00 PM", "8:30 PM", folk_set.clone()).unwrap();
+    booker.book("One Direction", "Madison Square Garden", opening_night, "9:00 PM", "11:00 PM", pop_set.clone()).unwrap();
+    booker.book("Holly", "The Basement", opening_night, "11:30 PM", "12:30 AM", folk_set.clone()).unwrap(); // Runs past midnight
+    let attempts = [
+        ("Backstreet Boys", "The Basement", next_night, "9:00 PM", "11:00 PM"), // 32 of them won't fit
+        ("Holly", "Madison Square Garden", opening_night, "10:00 PM", "10:30 PM"), // Venue's taken
+        ("Holly", "Madison Square Garden", next_night, "12:15 AM", "12:45 AM"), // Still on stage at the Basement
+        ("One Direction", "Madison Square Garden", next_night, "9:00 PM", "10:00 PM"), // Setlist is longer than the slot
+        ("Holly", "The Blue Note", next_night, "9:00 PM", "10:00 PM"),
+    ];
+    for (act, venue, date, start, end) in attempts {
+        let setlist = if act == "Holly" { folk_set.clone() } else { pop_set.clone() };
+        if let Err(error) = booker.book(act, venue, date, start, end, setlist) {
+            println!("{} at {}: {}", act, venue, error);
+        }
+    }
+    if let Err(error) = booker.add_venue(Venue::new("The Basement", 40, 6, 4)) { // Would shrink the stage under Holly's gigs
+        println!("{}", error);
+    }
+    for gig in booker.gigs() {
+        println!("{} --> break even at {} a ticket", gig, booker.break_even_ticket(gig.id).unwrap());
+    }
+    booker.cancel(holly_gig).unwrap();
+    let pop_acts: Vec<&str> = booker.acts_for(&[Genre::Pop]).iter().map(|act| act.name.as_str()).collect();
+    println!("Pop acts for the free night --> {:?}, gigs left --> {}", pop_acts, booker.gigs().len());
+
+    println!("Generic Numbers");
+    use numeric::{Vector2, Vector3};
+    let scores = [88, 92, 79, 95, 61];
+    let hours = [1.5_f32, 2.25, 0.75];
+    println!("Sum --> {} and {}", numeric::sum(scores), numeric::sum(hours));
+    println!("Mean --> {:?}, variance --> {:?}, std dev --> {:.2}", numeric::mean(scores), numeric::variance(scores), numeric::std_dev(scores).unwrap());
+    println!("Mean of nothing --> {:?}", numeric::mean(Vec::<u64>::new()));
+    let near_max = [u32::MAX - 5, 10, 3];
+    println!("Checked sum --> {:?}, saturating sum --> {}", numeric::checked_sum(near_max), numeric::saturating_sum(near_max));
+    println!("Checked float sum --> {:?}", numeric::checked_sum([f64::MAX, f64::MAX]));
+
+    let a = Vector2::new(3, 4);
+    let b = Vector2::new(-2, 1);
+    println!("{} + {} = {}, {} - {} = {}, {} * 3 = {}, -{} = {}", a, b, a + b, a, b, a - b, a, a * 3, a, -a);
+    println!("{} . {} = {}, {} x {} = {}, |{}| = {}", a, b, a.dot(b), a, b, a.cross(b), a, a.length());
+    let wide = Vector2::new(i32::MAX, 1);
+    println!("Checked dot of {} with {} --> {:?}, of {} with itself --> {:?}", a, b, a.checked_dot(b), wide, wide.checked_dot(wide));
+    let x = Vector3::new(1.0, 0.0, 0.0);
+    let y = Vector3::new(0.0, 1.0, 0.0);
+    println!("{} x {} = {}, length {}", x, y, x.cross(y), x.cross(y).length());
+    let big = Vector3::new(i64::MAX / 2, 3, 0);
+    println!("Checked dot of {} with itself --> {:?}", big, big.checked_dot(big));
 }
@@ -0,0 +1,334 @@
+// Exact money. Amounts are whole minor units (cents for dollars, nothing smaller than a yen) in an i64, so adding and
+// subtracting never drifts the way f64 does. Anything that can produce a fraction of a minor unit, like multiplying by a
+// ratio, asks for a Rounding mode, and allocate splits an amount so the parts always add back up to the exact total.
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Currency {
+    Usd,
+    Eur,
+    Gbp,
+    Jpy,
+}
+
+impl Currency {
+    // Digits after the decimal point
+    pub fn minor_digits(&self) -> u32 {
+        match self {
+            Currency::Jpy => 0,
+            _ => 2,
+        }
+    }
+
+    pub fn minor_per_major(&self) -> i64 {
+        10_i64.pow(self.minor_digits())
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Usd => "$",
+            Currency::Eur => "€",
+            Currency::Gbp => "£",
+            Currency::Jpy => "¥",
+        }
+    }
+}
+
+impl Display for Currency { // The ISO 4217 code
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Currency::Usd => write!(f, "USD"),
+            Currency::Eur => write!(f, "EUR"),
+            Currency::Gbp => write!(f, "GBP"),
+            Currency::Jpy => write!(f, "JPY"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Floor,    // Toward negative infinity
+    Ceiling,  // Toward positive infinity
+    HalfUp,   // Halves go away from zero
+    HalfEven, // Halves go to the even neighbour (banker's rounding)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoneyError {
+    CurrencyMismatch(Currency, Currency),
+    Overflow,
+    ZeroDenominator,
+    NoShares, // Allocating with no ratios, or ratios that are all zero
+}
+
+impl Display for MoneyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoneyError::CurrencyMismatch(left, right) => write!(f, "Can't combine {} with {}", left, right),
+            MoneyError::Overflow => write!(f, "The amount is too large to represent"),
+            MoneyError::ZeroDenominator => write!(f, "Can't divide money by zero"),
+            MoneyError::NoShares => write!(f, "There are no shares to allocate between"),
+        }
+    }
+}
+
+// Divides exactly, then rounds the quotient the way the caller asked
+fn divide(numerator: i128, denominator: i128, rounding: Rounding) -> i128 {
+    let (numerator, denominator) = if denominator < 0 { (-numerator, -denominator) } else { (numerator, denominator) };
+    let quotient = numerator / denominator; // Truncated toward zero
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return quotient;
+    }
+    let away = if numerator < 0 { -1 } else { 1 };
+    let twice_remainder = 2 * remainder.abs();
+    match rounding {
+        Rounding::Floor => if numerator < 0 { quotient - 1 } else { quotient },
+        Rounding::Ceiling => if numerator > 0 { quotient + 1 } else { quotient },
+        Rounding::HalfUp => if twice_remainder >= denominator { quotient + away } else { quotient },
+        Rounding::HalfEven => {
+            if twice_remainder > denominator || (twice_remainder == denominator && quotient % 2 != 0) {
+                quotient + away
+            } else {
+                quotient
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+
+impl Money {
+    pub const fn from_minor(minor: i64, currency: Currency) -> Self {
+        Self { minor, currency }
+    }
+
+    // Panics if the amount doesn't fit, like the operators below
+    pub fn from_major(major: i64, currency: Currency) -> Self {
+        Self::checked_from_major(major, currency).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn checked_from_major(major: i64, currency: Currency) -> Result<Self, MoneyError> {
+        let minor = major.checked_mul(currency.minor_per_major()).ok_or(MoneyError::Overflow)?;
+        Ok(Self::from_minor(minor, currency))
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::from_minor(0, currency)
+    }
+
+    // For bridging from the f64 amounts elsewhere in this module, e.g. Taxable::tax_bill
+    pub fn from_f64(amount: f64, currency: Currency) -> Result<Self, MoneyError> {
+        let minor = (amount * currency.minor_per_major() as f64).round();
+        if !minor.is_finite() || minor.abs() >= i64::MAX as f64 {
+            return Err(MoneyError::Overflow);
+        }
+        Ok(Self::from_minor(minor as i64, currency))
+    }
+
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, other.currency));
+        }
+        Ok(())
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
+        self.same_currency(&other)?;
+        let minor = self.minor.checked_add(other.minor).ok_or(MoneyError::Overflow)?;
+        Ok(Money::from_minor(minor, self.currency))
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, MoneyError> {
+        self.same_currency(&other)?;
+        let minor = self.minor.checked_sub(other.minor).ok_or(MoneyError::Overflow)?;
+        Ok(Money::from_minor(minor, self.currency))
+    }
+
+    // Multiplies by numerator / denominator, e.g. (8, 100) for 8% tax or (1, 3) for a third
+    pub fn mul_ratio(self, numerator: i64, denominator: i64, rounding: Rounding) -> Result<Money, MoneyError> {
+        if denominator == 0 {
+            return Err(MoneyError::ZeroDenominator);
+        }
+        let minor = divide(self.minor as i128 * numerator as i128, denominator as i128, rounding);
+        let minor = i64::try_from(minor).map_err(|_| MoneyError::Overflow)?;
+        Ok(Money::from_minor(minor, self.currency))
+    }
+
+    // Splits the amount in proportion to the ratios. Every part is rounded down, then the minor units left over are handed
+    // out one at a time starting from the first part, so the parts always sum to exactly the original amount.
+    pub fn allocate(self, ratios: &[u64]) -> Result<Vec<Money>, MoneyError> {
+        let total: i128 = ratios.iter().map(|&ratio| ratio as i128).sum();
+        if total == 0 {
+            return Err(MoneyError::NoShares);
+        }
+        let mut parts: Vec<i128> = ratios.iter().map(|&ratio| divide(self.minor as i128 * ratio as i128, total, Rounding::Floor)).collect();
+        let mut leftover = self.minor as i128 - parts.iter().sum::<i128>(); // Always between 0 and the number of parts
+        for (part, &ratio) in parts.iter_mut().zip(ratios) {
+            if leftover == 0 {
+                break;
+            }
+            if ratio > 0 {
+                *part += 1;
+                leftover -= 1;
+            }
+        }
+        Ok(parts.into_iter().map(|part| Money::from_minor(part as i64, self.currency)).collect())
+    }
+
+    pub fn split(self, ways: u32) -> Result<Vec<Money>, MoneyError> {
+        self.allocate(&vec![1; ways as usize])
+    }
+}
+
+// The operators panic on mixed currencies or overflow, the same way integer overflow does. Use the checked_ methods when
+// the currencies come from outside the program.
+impl Add for Money {
+    type Output = Money;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl Mul<i64> for Money { // Whole quantities are always exact, use mul_ratio for anything fractional
+    type Output = Money;
+    fn mul(self, rhs: i64) -> Self::Output {
+        let minor = self.minor.checked_mul(rhs).unwrap_or_else(|| panic!("{}", MoneyError::Overflow));
+        Money::from_minor(minor, self.currency)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+    fn neg(self) -> Self::Output {
+        Money::from_minor(-self.minor, self.currency)
+    }
+}
+
+impl PartialOrd for Money { // Amounts in different currencies can't be compared
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(self.minor.cmp(&other.minor))
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let per_major = self.currency.minor_per_major() as u64;
+        let minor = self.minor.unsigned_abs();
+        let text = match self.currency.minor_digits() {
+            0 => format!("{}{}{}", sign, self.currency.symbol(), minor),
+            digits => format!(
+                "{}{}{}.{:0width$}",
+                sign,
+                self.currency.symbol(),
+                minor / per_major,
+                minor % per_major,
+                width = digits as usize
+            ),
+        };
+        f.pad(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cents(minor: i64) -> Money {
+        Money::from_minor(minor, Currency::Usd)
+    }
+
+    #[test]
+    fn every_rounding_mode_both_signs() {
+        // (amount, numerator, denominator, [Floor, Ceiling, HalfUp, HalfEven])
+        let cases = [
+            (10, 1, 4, [2, 3, 3, 2]),      // 2.5
+            (-10, 1, 4, [-3, -2, -3, -2]), // -2.5
+            (7, 1, 2, [3, 4, 4, 4]),       // 3.5
+            (-7, 1, 2, [-4, -3, -4, -4]),  // -3.5
+            (100, 1, 3, [33, 34, 33, 33]), // 33.33
+            (-100, 1, 3, [-34, -33, -33, -33]),
+            (100, 2, 3, [66, 67, 67, 67]), // 66.67
+            (100, 2, -3, [-67, -66, -67, -67]),
+            (90, 1, 3, [30, 30, 30, 30]),  // Exact
+        ];
+        let modes = [Rounding::Floor, Rounding::Ceiling, Rounding::HalfUp, Rounding::HalfEven];
+        for (amount, numerator, denominator, expected) in cases {
+            for (rounding, expected) in modes.into_iter().zip(expected) {
+                assert_eq!(cents(amount).mul_ratio(numerator, denominator, rounding), Ok(cents(expected)), "{} * {}/{} {:?}", amount, numerator, denominator, rounding);
+            }
+        }
+    }
+
+    #[test]
+    fn mul_ratio_errors() {
+        assert_eq!(cents(100).mul_ratio(1, 0, Rounding::Floor), Err(MoneyError::ZeroDenominator));
+        assert_eq!(cents(i64::MAX).mul_ratio(2, 1, Rounding::Floor), Err(MoneyError::Overflow));
+        assert_eq!(cents(i64::MAX).mul_ratio(2, 2, Rounding::Floor), Ok(cents(i64::MAX))); // Only the result has to fit
+    }
+
+    #[test]
+    fn allocate_loses_nothing() {
+        let cases: [(i64, &[u64]); 7] = [
+            (10_000, &[1, 1, 1]),
+            (1, &[1, 1, 1]),
+            (-10_000, &[1, 1, 1]),
+            (1_000, &[50, 30, 20]),
+            (999, &[0, 7, 0, 3]),
+            (5, &[0, 0, 1]),
+            (i64::MAX, &[u64::MAX, 1, u64::MAX]),
+        ];
+        for (amount, ratios) in cases {
+            let parts = cents(amount).allocate(ratios).unwrap();
+            assert_eq!(parts.len(), ratios.len());
+            assert_eq!(parts.iter().map(|part| part.minor() as i128).sum::<i128>(), amount as i128, "{} {:?}", amount, ratios);
+            for (part, ratio) in parts.iter().zip(ratios) {
+                if *ratio == 0 {
+                    assert_eq!(part.minor(), 0); // A zero share never gets a leftover cent
+                }
+            }
+        }
+        assert_eq!(cents(10_000).split(3).unwrap(), [cents(3_334), cents(3_333), cents(3_333)]);
+        assert_eq!(cents(-10_000).split(3).unwrap(), [cents(-3_333), cents(-3_333), cents(-3_334)]);
+        assert_eq!(cents(100).allocate(&[0, 0]), Err(MoneyError::NoShares));
+        assert_eq!(cents(100).allocate(&[]), Err(MoneyError::NoShares));
+    }
+
+    #[test]
+    fn from_major_checks_for_overflow() {
+        assert_eq!(Money::checked_from_major(12, Currency::Usd), Ok(cents(1_200)));
+        assert_eq!(Money::checked_from_major(12, Currency::Jpy), Ok(Money::from_minor(12, Currency::Jpy)));
+        assert_eq!(Money::checked_from_major(i64::MAX / 10, Currency::Usd), Err(MoneyError::Overflow));
+        assert_eq!(Money::checked_from_major(i64::MAX, Currency::Jpy), Ok(Money::from_minor(i64::MAX, Currency::Jpy)));
+    }
+}
@@ -0,0 +1,371 @@
+// Generic arithmetic beyond add_two_numbers. Number collects the operator traits a numeric type needs plus checked and
+// saturating versions of them, so sum, mean and variance work the same for integers and floats. Vector2 and Vector3 are
+// built on top with the usual operators, and the tests check the algebraic laws they should obey on random inputs.
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::add_two_numbers;
+
+pub trait Number: Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    const ZERO: Self;
+    fn to_f64(self) -> f64;
+    // None on overflow for integers, and when the result isn't finite for floats
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    // Clamped to the type's MIN or MAX instead
+    fn saturating_add(self, other: Self) -> Self;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {$(
+        impl Number for $t {
+            const ZERO: Self = 0;
+            fn to_f64(self) -> f64 { self as f64 }
+            fn checked_add(self, other: Self) -> Option<Self> { <$t>::checked_add(self, other) }
+            fn checked_mul(self, other: Self) -> Option<Self> { <$t>::checked_mul(self, other) }
+            fn saturating_add(self, other: Self) -> Self { <$t>::saturating_add(self, other) }
+        }
+    )*};
+}
+
+macro_rules! float {
+    ($($t:ty),*) => {$(
+        impl Number for $t {
+            const ZERO: Self = 0.0;
+            fn to_f64(self) -> f64 { self as f64 }
+            fn checked_add(self, other: Self) -> Option<Self> { Some(self + other).filter(|total| total.is_finite()) }
+            fn checked_mul(self, other: Self) -> Option<Self> { Some(self * other).filter(|total| total.is_finite()) }
+            fn saturating_add(self, other: Self) -> Self { (self + other).clamp(<$t>::MIN, <$t>::MAX) }
+        }
+    )*};
+}
+
+integer!(i32, i64, u32, u64);
+float!(f32, f64);
+
+// Panics on integer overflow in debug builds, just like adding them up by hand
+pub fn sum<T: Number>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ZERO, add_two_numbers)
+}
+
+pub fn checked_sum<T: Number>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ZERO, T::checked_add)
+}
+
+// Once the running total hits MIN or MAX it can still come back, e.g. [MAX, 1, -1] is MAX - 1
+pub fn saturating_sum<T: Number>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ZERO, T::saturating_add)
+}
+
+// In f64 so integer inputs can't overflow and the mean of [1, 2] is 1.5. None when there are no values.
+pub fn mean<T: Number>(values: impl IntoIterator<Item = T>) -> Option<f64> {
+    Some(stats(values)?.1)
+}
+
+// Population variance
+pub fn variance<T: Number>(values: impl IntoIterator<Item = T>) -> Option<f64> {
+    let (count, _, squares) = stats(values)?;
+    Some(squares / count as f64)
+}
+
+pub fn std_dev<T: Number>(values: impl IntoIterator<Item = T>) -> Option<f64> {
+    variance(values).map(f64::sqrt)
+}
+
+// Welford's algorithm: count, mean and the sum of squared differences from the mean in one pass, without the
+// cancellation you get from sum(x^2) - sum(x)^2
+fn stats<T: Number>(values: impl IntoIterator<Item = T>) -> Option<(u64, f64, f64)> {
+    let (mut count, mut mean, mut squares) = (0u64, 0.0, 0.0);
+    for value in values {
+        let value = value.to_f64();
+        count += 1;
+        let delta = value - mean;
+        mean += delta / count as f64;
+        squares += delta * (value - mean);
+    }
+    if count == 0 { None } else { Some((count, mean, squares)) }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Number> Vector2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    pub fn dot(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    // The z part of the 3D cross product: positive when `other` is counterclockwise from self
+    pub fn cross(self, other: Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn checked_dot(self, other: Self) -> Option<T> {
+        checked_sum([self.x.checked_mul(other.x)?, self.y.checked_mul(other.y)?])
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).to_f64().sqrt()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Number> Vector3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn dot(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Self) -> Self {
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn checked_dot(self, other: Self) -> Option<T> {
+        let products = [self.x.checked_mul(other.x)?, self.y.checked_mul(other.y)?, self.z.checked_mul(other.z)?];
+        checked_sum(products)
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).to_f64().sqrt()
+    }
+}
+
+impl<T: Number> Add for Vector2<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Number> Sub for Vector2<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Number> Mul<T> for Vector2<T> { // Scaling, vector * scalar
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Neg for Vector2<T> { // Only for signed types
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Number> Add for Vector3<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Number> Sub for Vector3<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Number> Mul<T> for Vector3<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Neg for Vector3<T> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Display> Display for Vector2<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: Display> Display for Vector3<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::Rng;
+
+    // Random inputs for a law. Integer vector parts stay small so nothing overflows, the float vectors have fractional
+    // parts so rounding shows up, and the scalar values cover all of i32 so the checked and saturating sums get pushed
+    // past the edges.
+    #[derive(Debug, Clone)]
+    struct Case {
+        a: Vector3<i64>,
+        b: Vector3<i64>,
+        c: Vector3<i64>,
+        k: i64,
+        p: Vector2<i64>,
+        q: Vector2<i64>,
+        fa: Vector3<f64>,
+        fb: Vector3<f64>,
+        fc: Vector3<f64>,
+        fk: f64,
+        values: Vec<i32>,
+    }
+
+    impl Case {
+        fn random(rng: &mut Rng) -> Self {
+            let mut small = || (rng.next_u64() % 2001) as i64 - 1000;
+            let mut vector = || Vector3::new(small(), small(), small());
+            let (a, b, c) = (vector(), vector(), vector());
+            let (p, q) = (Vector2::new(a.x, b.y), Vector2::new(c.z, a.y));
+            let mut float = || rng.next_f64() * 2000.0 - 1000.0;
+            let mut float_vector = || Vector3::new(float(), float(), float());
+            let (fa, fb, fc) = (float_vector(), float_vector(), float_vector());
+            let fk = rng.next_f64() * 20.0 - 10.0;
+            let k = (rng.next_u64() % 2001) as i64 - 1000;
+            let length = rng.next_u64() % 8;
+            let values = (0..length).map(|_| rng.next_u64() as i32).collect();
+            Self { a, b, c, k, p, q, fa, fb, fc, fk, values }
+        }
+    }
+
+    fn close(a: f64, b: f64, scale: f64) -> bool {
+        (a - b).abs() <= 1e-9 * scale.max(1.0)
+    }
+
+    fn close_vectors(a: Vector3<f64>, b: Vector3<f64>, scale: f64) -> bool {
+        close(a.x, b.x, scale) && close(a.y, b.y, scale) && close(a.z, b.z, scale)
+    }
+
+    struct Law {
+        name: &'static str,
+        holds: fn(&Case) -> bool,
+    }
+
+    const LAWS: [Law; 23] = [
+        Law { name: "a + b = b + a", holds: |case| case.a + case.b == case.b + case.a },
+        Law { name: "(a + b) + c = a + (b + c)", holds: |case| (case.a + case.b) + case.c == case.a + (case.b + case.c) },
+        Law { name: "a + 0 = a", holds: |case| case.a + Vector3::default() == case.a },
+        Law { name: "a + -a = 0", holds: |case| case.a + -case.a == Vector3::default() },
+        Law { name: "a - b = a + -b", holds: |case| case.a - case.b == case.a + -case.b },
+        Law { name: "(a + b) * k = a * k + b * k", holds: |case| (case.a + case.b) * case.k == case.a * case.k + case.b * case.k },
+        Law { name: "a . b = b . a", holds: |case| case.a.dot(case.b) == case.b.dot(case.a) },
+        Law { name: "a x b = -(b x a)", holds: |case| case.a.cross(case.b) == -case.b.cross(case.a) },
+        Law { name: "(a x b) . a = 0 and (a x b) . b = 0", holds: |case| {
+            let cross = case.a.cross(case.b);
+            cross.dot(case.a) == 0 && cross.dot(case.b) == 0
+        } },
+        Law { name: "checked_dot is the dot product when nothing overflows", holds: |case| {
+            case.a.checked_dot(case.b) == Some(case.a.dot(case.b))
+        } },
+        Law { name: "2D a + b = b + a", holds: |case| case.p + case.q == case.q + case.p },
+        Law { name: "2D a - b = a + -b", holds: |case| case.p - case.q == case.p + -case.q },
+        Law { name: "2D (a + b) * k = a * k + b * k", holds: |case| (case.p + case.q) * case.k == case.p * case.k + case.q * case.k },
+        Law { name: "2D a x b = -(b x a) and a x a = 0", holds: |case| {
+            case.p.cross(case.q) == -case.q.cross(case.p) && case.p.cross(case.p) == 0
+        } },
+        Law { name: "2D checked_dot is the dot product when nothing overflows", holds: |case| {
+            case.p.checked_dot(case.q) == Some(case.p.dot(case.q)) && case.p.dot(case.q) == case.q.dot(case.p)
+        } },
+        Law { name: "float (a + b) + c is close to a + (b + c)", holds: |case| {
+            close_vectors((case.fa + case.fb) + case.fc, case.fa + (case.fb + case.fc), 3000.0)
+        } },
+        Law { name: "float (a x b) . a and (a x b) . b are close to 0", holds: |case| {
+            let cross = case.fa.cross(case.fb);
+            let scale = case.fa.length() * case.fb.length() * case.fa.length().max(case.fb.length());
+            close(cross.dot(case.fa), 0.0, scale) && close(cross.dot(case.fb), 0.0, scale)
+        } },
+        Law { name: "float |a * k| is close to |a| * |k|", holds: |case| {
+            close((case.fa * case.fk).length(), case.fa.length() * case.fk.abs(), case.fa.length() * case.fk.abs())
+        } },
+        Law { name: "checked_sum is None exactly when a running total overflows", holds: |case| {
+            let mut running = 0i64;
+            let totals: Vec<i64> = case.values.iter().map(|&value| { running += value as i64; running }).collect();
+            let expected = if totals.iter().all(|&total| i32::try_from(total).is_ok()) { Some(running as i32) } else { None };
+            checked_sum(case.values.iter().copied()) == expected
+        } },
+        Law { name: "saturating_sum matches checked_sum when it doesn't overflow", holds: |case| {
+            let values = case.values.iter().copied();
+            checked_sum(values.clone()).is_none_or(|total| saturating_sum(values) == total)
+        } },
+        Law { name: "saturating_sum of values with one sign is the total clamped", holds: |case| {
+            let magnitudes: Vec<i32> = case.values.iter().map(|value| value.saturating_abs()).collect();
+            let total: i64 = magnitudes.iter().map(|&value| value as i64).sum();
+            let negated: Vec<i32> = magnitudes.iter().map(|value| -value).collect();
+            saturating_sum(magnitudes) == total.min(i32::MAX as i64) as i32
+                && saturating_sum(negated) == (-total).max(i32::MIN as i64) as i32
+        } },
+        Law { name: "mean is between the smallest and largest value", holds: |case| match mean(case.values.iter().copied()) {
+            Some(mean) => {
+                let (smallest, largest) = (case.values.iter().min(), case.values.iter().max());
+                smallest.zip(largest).is_some_and(|(&smallest, &largest)| smallest as f64 - 1e-6 <= mean && mean <= largest as f64 + 1e-6)
+            }
+            None => case.values.is_empty(),
+        } },
+        Law { name: "variance doesn't change when every value shifts by k", holds: |case| {
+            let shifted = case.values.iter().map(|&value| value as f64 + case.k as f64);
+            match (variance(case.values.iter().copied()), variance(shifted)) {
+                (Some(before), Some(after)) => close(before, after, before),
+                (before, after) => before.is_none() && after.is_none(),
+            }
+        } },
+    ];
+
+    // Every law gets the same random cases, so a failure shows up again on every run
+    #[test]
+    fn every_law_holds() {
+        let mut rng = Rng::new(7);
+        let cases: Vec<Case> = (0..500).map(|_| Case::random(&mut rng)).collect();
+        for law in &LAWS {
+            if let Some(case) = cases.iter().find(|case| !(law.holds)(case)) {
+                panic!("{} fails for {:?}", law.name, case);
+            }
+        }
+    }
+
+    #[test]
+    fn checked_dot_catches_overflow() {
+        let big = Vector2::new(3_037_000_499i64, 3_037_000_499); // Each square fits in an i64, their sum doesn't
+        assert!(big.x.checked_mul(big.x).is_some());
+        assert_eq!(big.checked_dot(big), None);
+        assert_eq!(Vector2::new(i64::MAX, 0).checked_dot(Vector2::new(2, 0)), None);
+        assert_eq!(Vector3::new(i64::MAX / 2, 3, 0).checked_dot(Vector3::new(i64::MAX / 2, 3, 0)), None);
+        assert_eq!(Vector2::new(f64::MAX, 0.0).checked_dot(Vector2::new(2.0, 0.0)), None);
+    }
+
+    #[test]
+    fn saturating_sum_comes_back_from_the_edge() {
+        assert_eq!(saturating_sum([i32::MAX, 1, -1]), i32::MAX - 1);
+        assert_eq!(saturating_sum([u32::MAX - 5, 10, 3]), u32::MAX);
+        assert_eq!(saturating_sum([f64::MAX, f64::MAX]), f64::MAX);
+        assert_eq!(saturating_sum(Vec::<i64>::new()), 0);
+    }
+}
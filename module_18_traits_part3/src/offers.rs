@@ -0,0 +1,205 @@
+// Comparing job offers on more than salary. Job's PartialOrd only looks at salary, so this scores each offer on salary,
+// commute, benefits and time off, each scaled 0 to 100 against the other offers being compared, and combines them with
+// weights the user picks. Every score keeps its explanation so the ranking can say why one offer beat another.
+use std::fmt::{Display, Formatter};
+
+use crate::Job;
+
+// Job::commute_time is hours per workday, there and back
+pub const WORKDAYS_PER_YEAR: u32 = 260;
+pub const HOURS_PER_WORKDAY: f64 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Benefits {
+    pub health_insurance: u32,  // What the employer pays toward it per year
+    pub retirement_match: f64,  // Fraction of salary, e.g. 0.04
+    pub pto_days: u32,
+    pub remote_days_per_week: u32,
+}
+
+impl Benefits {
+    // Yearly dollar value of everything that isn't salary or time off
+    pub fn cash_value(&self, salary: u32) -> f64 {
+        self.health_insurance as f64 + salary as f64 * self.retirement_match
+    }
+}
+
+pub struct Offer {
+    pub company: String,
+    pub job: Job,
+    pub benefits: Benefits,
+}
+
+impl Offer {
+    pub fn new(company: &str, job: Job, benefits: Benefits) -> Self {
+        Self { company: company.to_string(), job, benefits }
+    }
+
+    pub fn workdays(&self) -> u32 {
+        WORKDAYS_PER_YEAR.saturating_sub(self.benefits.pto_days)
+    }
+
+    pub fn commute_hours_per_year(&self) -> f64 {
+        let office_days_per_week = 5u32.saturating_sub(self.benefits.remote_days_per_week) as f64;
+        self.workdays() as f64 * office_days_per_week / 5.0 * self.job.commute_time as f64
+    }
+
+    // Salary plus benefits over every hour the job takes, commuting included
+    pub fn effective_hourly(&self) -> f64 {
+        let hours = self.workdays() as f64 * HOURS_PER_WORKDAY + self.commute_hours_per_year();
+        if hours <= 0.0 {
+            return 0.0;
+        }
+        (self.job.salary as f64 + self.benefits.cash_value(self.job.salary)) / hours
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+    pub salary: f64,
+    pub commute: f64,
+    pub benefits: f64,
+    pub time_off: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self { salary: 0.4, commute: 0.25, benefits: 0.2, time_off: 0.15 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightError {
+    Negative(&'static str),
+    AllZero,
+}
+
+impl Display for WeightError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeightError::Negative(criterion) => write!(f, "The {} weight can't be negative", criterion),
+            WeightError::AllZero => write!(f, "At least one weight has to be above zero"),
+        }
+    }
+}
+
+impl Weights {
+    // The weights as fractions of their sum, in the same order as CRITERIA
+    fn normalized(&self) -> Result<[f64; 4], WeightError> {
+        let weights = [self.salary, self.commute, self.benefits, self.time_off];
+        for (weight, criterion) in weights.iter().zip(CRITERIA) {
+            if weight.is_nan() || *weight < 0.0 {
+                return Err(WeightError::Negative(criterion.name));
+            }
+        }
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return Err(WeightError::AllZero);
+        }
+        Ok(weights.map(|weight| weight / total))
+    }
+}
+
+struct Criterion {
+    name: &'static str,
+    higher_is_better: bool,
+    value: fn(&Offer) -> f64,
+    describe: fn(f64) -> String,
+}
+
+const CRITERIA: [Criterion; 4] = [
+    Criterion { name: "salary", higher_is_better: true, value: |offer| offer.job.salary as f64, describe: |value| format!("${:.0}/year", value) },
+    Criterion { name: "commute", higher_is_better: false, value: Offer::commute_hours_per_year, describe: |value| format!("{:.0} hours/year", value) },
+    Criterion {
+        name: "benefits",
+        higher_is_better: true,
+        value: |offer| offer.benefits.cash_value(offer.job.salary),
+        describe: |value| format!("${:.0}/year", value),
+    },
+    Criterion { name: "time off", higher_is_better: true, value: |offer| offer.benefits.pto_days as f64, describe: |value| format!("{:.0} days", value) },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CriterionScore {
+    pub criterion: &'static str,
+    pub value: String,
+    pub score: f64, // 0 for the worst offer on this criterion, 100 for the best
+    pub weight: f64,
+    pub explanation: &'static str,
+}
+
+impl CriterionScore {
+    pub fn contribution(&self) -> f64 {
+        self.score * self.weight
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub rank: usize,
+    pub company: String,
+    pub total: f64,
+    pub effective_hourly: f64,
+    pub scores: Vec<CriterionScore>,
+}
+
+impl Display for Evaluation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "#{} {} --> {:.1} points, ${:.2}/hour including commute", self.rank, self.company, self.total, self.effective_hourly)?;
+        for score in &self.scores {
+            writeln!(
+                f,
+                "   {:<9} {:>16}  {:>5.1} x {:.2} = {:>5.1}  {}",
+                score.criterion,
+                score.value,
+                score.score,
+                score.weight,
+                score.contribution(),
+                score.explanation
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Scores every offer and sorts them best first. Ties keep the order the offers were given in.
+pub fn rank(offers: &[Offer], weights: &Weights) -> Result<Vec<Evaluation>, WeightError> {
+    let weights = weights.normalized()?;
+    let mut evaluations: Vec<Evaluation> = offers
+        .iter()
+        .map(|offer| Evaluation {
+            rank: 0,
+            company: offer.company.clone(),
+            total: 0.0,
+            effective_hourly: offer.effective_hourly(),
+            scores: Vec::new(),
+        })
+        .collect();
+
+    for (criterion, weight) in CRITERIA.iter().zip(weights) {
+        let values: Vec<f64> = offers.iter().map(criterion.value).collect();
+        let best = values.iter().copied().fold(f64::NAN, if criterion.higher_is_better { f64::max } else { f64::min });
+        let worst = values.iter().copied().fold(f64::NAN, if criterion.higher_is_better { f64::min } else { f64::max });
+        for (evaluation, value) in evaluations.iter_mut().zip(values) {
+            let (score, explanation) = if best == worst {
+                (100.0, "same for every offer")
+            } else if value == best {
+                (100.0, "best of the offers")
+            } else if value == worst {
+                (0.0, "worst of the offers")
+            } else {
+                (100.0 * (value - worst) / (best - worst), "between the best and worst")
+            };
+            evaluation.scores.push(CriterionScore { criterion: criterion.name, value: (criterion.describe)(value), score, weight, explanation });
+        }
+    }
+
+    for evaluation in evaluations.iter_mut() {
+        evaluation.total = evaluation.scores.iter().map(CriterionScore::contribution).sum();
+    }
+    evaluations.sort_by(|a, b| b.total.total_cmp(&a.total));
+    for (i, evaluation) in evaluations.iter_mut().enumerate() {
+        evaluation.rank = i + 1;
+    }
+    Ok(evaluations)
+}
@@ -0,0 +1,448 @@
+// Portfolio modeling on top of Investment<f64>. An Account compounds at its own frequency and can have yearly contributions
+// and withdrawals spread across its compounding periods. A Portfolio projects every account forward year by year, either at
+// each account's fixed rate or as a Monte Carlo simulation where each year's return is drawn from a seeded random number
+// generator, so the same seed always gives the same results.
+use std::fmt::{Display, Formatter};
+
+use crate::Investment;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Annually,
+    Quarterly,
+    Monthly,
+    Daily,
+}
+
+impl Frequency {
+    pub fn periods_per_year(&self) -> u32 {
+        match self {
+            Frequency::Annually => 1,
+            Frequency::Quarterly => 4,
+            Frequency::Monthly => 12,
+            Frequency::Daily => 365,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PortfolioError {
+    InvalidAmount(f64), // Zero, negative or not a number
+    InvalidBalance(f64), // Negative or not a number
+    InvalidRate(f64),    // Not a number, a return of -100% or worse, or a negative volatility
+    InsufficientFunds { requested: f64, balance: f64 },
+}
+
+impl Display for PortfolioError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortfolioError::InvalidAmount(amount) => write!(f, "{} is not a valid amount", amount),
+            PortfolioError::InvalidBalance(amount) => write!(f, "{} is not a valid balance", amount),
+            PortfolioError::InvalidRate(rate) => write!(f, "{} is not a valid rate", rate),
+            PortfolioError::InsufficientFunds { requested, balance } => {
+                write!(f, "Can't withdraw ${:.2}, the balance is only ${:.2}", requested, balance)
+            }
+        }
+    }
+}
+
+fn check_amount(amount: f64) -> Result<(), PortfolioError> {
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(PortfolioError::InvalidAmount(amount));
+    }
+    Ok(())
+}
+
+// Starting balances and yearly flows can be zero
+fn check_balance(amount: f64) -> Result<(), PortfolioError> {
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(PortfolioError::InvalidBalance(amount));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub name: String,
+    balance: f64,
+    annual_rate: f64, // Expected yearly return, e.g. 0.07 for 7%
+    volatility: f64,  // Standard deviation of the yearly return, only used by Monte Carlo
+    frequency: Frequency,
+    yearly_contribution: f64,
+    yearly_withdrawal: f64,
+}
+
+impl Account {
+    pub fn new(name: &str, balance: f64, annual_rate: f64, frequency: Frequency) -> Result<Self, PortfolioError> {
+        check_balance(balance)?;
+        if !annual_rate.is_finite() || annual_rate <= -1.0 {
+            return Err(PortfolioError::InvalidRate(annual_rate));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            balance,
+            annual_rate,
+            volatility: 0.0,
+            frequency,
+            yearly_contribution: 0.0,
+            yearly_withdrawal: 0.0,
+        })
+    }
+
+    pub fn with_contribution(mut self, yearly: f64) -> Result<Self, PortfolioError> {
+        check_balance(yearly)?;
+        self.yearly_contribution = yearly;
+        Ok(self)
+    }
+
+    pub fn with_withdrawal(mut self, yearly: f64) -> Result<Self, PortfolioError> {
+        check_balance(yearly)?;
+        self.yearly_withdrawal = yearly;
+        Ok(self)
+    }
+
+    pub fn with_volatility(mut self, volatility: f64) -> Result<Self, PortfolioError> {
+        if !volatility.is_finite() || volatility < 0.0 {
+            return Err(PortfolioError::InvalidRate(volatility));
+        }
+        self.volatility = volatility;
+        Ok(self)
+    }
+
+    pub fn contribute(&mut self, amount: f64) -> Result<(), PortfolioError> {
+        check_amount(amount)?;
+        self.balance += amount;
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, amount: f64) -> Result<(), PortfolioError> {
+        check_amount(amount)?;
+        if amount > self.balance {
+            return Err(PortfolioError::InsufficientFunds { requested: amount, balance: self.balance });
+        }
+        self.balance -= amount;
+        Ok(())
+    }
+
+    // Compounds one year at the given annual rate. Each period earns its share of the rate, then receives its share of the
+    // yearly contribution and pays its share of the yearly withdrawal, which can't take the balance below zero.
+    fn advance_year(&mut self, annual_rate: f64) -> YearFlows {
+        let periods = self.frequency.periods_per_year();
+        let mut flows = YearFlows::default();
+        for _ in 0..periods {
+            let interest = self.balance * annual_rate / periods as f64;
+            let contribution = self.yearly_contribution / periods as f64;
+            self.balance += interest + contribution;
+            let withdrawal = (self.yearly_withdrawal / periods as f64).min(self.balance.max(0.0));
+            self.balance -= withdrawal;
+            flows.interest += interest;
+            flows.contributed += contribution;
+            flows.withdrawn += withdrawal;
+        }
+        flows
+    }
+}
+
+impl Investment<f64> for Account {
+    fn double_amount(&mut self) {
+        self.balance *= 2.0;
+    }
+    fn get_amount(&self) -> f64 {
+        self.balance
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct YearFlows {
+    interest: f64,
+    contributed: f64,
+    withdrawn: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct YearRow {
+    pub year: u32,
+    pub balances: Vec<f64>, // One per account, in portfolio order
+    pub contributed: f64,
+    pub withdrawn: f64,
+    pub interest: f64, // Negative in a losing year
+}
+
+impl YearRow {
+    pub fn total(&self) -> f64 {
+        self.balances.iter().sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    pub accounts: Vec<String>,
+    pub rows: Vec<YearRow>, // Year 0 is the starting point
+}
+
+impl Display for Projection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:>4}", "Year")?;
+        for name in &self.accounts {
+            write!(f, " {:>14}", name)?;
+        }
+        writeln!(f, " {:>14} {:>12} {:>12} {:>12}", "Total", "Contributed", "Withdrawn", "Growth")?;
+        for row in &self.rows {
+            write!(f, "{:>4}", row.year)?;
+            for balance in &row.balances {
+                write!(f, " {:>14.2}", balance)?;
+            }
+            writeln!(f, " {:>14.2} {:>12.2} {:>12.2} {:>12.2}", row.total(), row.contributed, row.withdrawn, row.interest)?;
+        }
+        Ok(())
+    }
+}
+
+// SplitMix64, small and good enough for simulations. Not for anything security related.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Box-Muller transform
+    pub fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        let u1 = 1.0 - self.next_f64(); // (0, 1] so the log is finite
+        let u2 = self.next_f64();
+        mean + std_dev * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Percentiles {
+    pub year: u32,
+    pub p10: f64,
+    pub p50: f64,
+    pub p90: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarlo {
+    pub trials: u32,
+    pub seed: u64,
+    pub years: Vec<Percentiles>, // Of the portfolio total
+    pub depleted: u32,           // Trials that ended with nothing left
+}
+
+impl Display for MonteCarlo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Monte Carlo: {} trials, seed {}", self.trials, self.seed)?;
+        writeln!(f, "{:>4} {:>14} {:>14} {:>14}", "Year", "10th pct", "Median", "90th pct")?;
+        for year in &self.years {
+            writeln!(f, "{:>4} {:>14.2} {:>14.2} {:>14.2}", year.year, year.p10, year.p50, year.p90)?;
+        }
+        write!(f, "Ran out of money in {} of {} trials", self.depleted, self.trials)
+    }
+}
+
+// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Portfolio {
+    accounts: Vec<Account>,
+}
+
+impl Portfolio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, account: Account) {
+        self.accounts.push(account);
+    }
+
+    pub fn account_mut(&mut self, name: &str) -> Option<&mut Account> {
+        self.accounts.iter_mut().find(|account| account.name == name)
+    }
+
+    pub fn total(&self) -> f64 {
+        self.accounts.iter().map(|account| account.get_amount()).sum()
+    }
+
+    // Runs a copy of the portfolio forward, asking rate_for for each account's return in each year
+    fn simulate<F: FnMut(&Account) -> f64>(&self, years: u32, mut rate_for: F) -> Vec<YearRow> {
+        let mut accounts = self.accounts.clone();
+        let mut rows = vec![YearRow {
+            year: 0,
+            balances: accounts.iter().map(|account| account.get_amount()).collect(),
+            contributed: 0.0,
+            withdrawn: 0.0,
+            interest: 0.0,
+        }];
+        for year in 1..=years {
+            let mut row = YearRow { year, balances: Vec::new(), contributed: 0.0, withdrawn: 0.0, interest: 0.0 };
+            for account in accounts.iter_mut() {
+                let rate = rate_for(account);
+                let flows = account.advance_year(rate);
+                row.balances.push(account.get_amount());
+                row.contributed += flows.contributed;
+                row.withdrawn += flows.withdrawn;
+                row.interest += flows.interest;
+            }
+            rows.push(row);
+        }
+        rows
+    }
+
+    // Every account earns exactly its expected rate every year
+    pub fn project(&self, years: u32) -> Projection {
+        Projection {
+            accounts: self.accounts.iter().map(|account| account.name.clone()).collect(),
+            rows: self.simulate(years, |account| account.annual_rate),
+        }
+    }
+
+    pub fn monte_carlo(&self, years: u32, trials: u32, seed: u64) -> MonteCarlo {
+        let mut rng = Rng::new(seed);
+        let mut totals: Vec<Vec<f64>> = vec![Vec::new(); years as usize + 1]; // Per year, one total per trial
+        let mut depleted = 0;
+        for _ in 0..trials {
+            let rows = self.simulate(years, |account| rng.normal(account.annual_rate, account.volatility).max(-1.0)); // Can't lose more than everything
+            for row in &rows {
+                totals[row.year as usize].push(row.total());
+            }
+            if rows.last().is_some_and(|row| row.total() <= 0.0) {
+                depleted += 1;
+            }
+        }
+
+        let years = totals
+            .iter_mut()
+            .enumerate()
+            .map(|(year, values)| {
+                values.sort_by(|a, b| a.total_cmp(b));
+                Percentiles { year: year as u32, p10: percentile(values, 10.0), p50: percentile(values, 50.0), p90: percentile(values, 90.0) }
+            })
+            .collect();
+        MonteCarlo { trials, seed, years, depleted }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(name: &str, balance: f64, annual_rate: f64, frequency: Frequency) -> Account {
+        Account::new(name, balance, annual_rate, frequency).unwrap()
+    }
+
+    fn portfolio() -> Portfolio {
+        let mut portfolio = Portfolio::new();
+        portfolio.add(account("Stocks", 10_000.0, 0.07, Frequency::Monthly).with_volatility(0.15).unwrap().with_contribution(1_200.0).unwrap());
+        portfolio.add(account("Bonds", 5_000.0, 0.03, Frequency::Quarterly).with_volatility(0.05).unwrap());
+        portfolio
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{} is not {}", actual, expected);
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_simulation() {
+        assert_eq!(portfolio().monte_carlo(10, 200, 42), portfolio().monte_carlo(10, 200, 42));
+    }
+
+    #[test]
+    fn different_seeds_give_different_simulations() {
+        let (first, second) = (portfolio().monte_carlo(10, 200, 42), portfolio().monte_carlo(10, 200, 43));
+        assert_eq!(first.years[0], second.years[0]); // Year 0 is the starting balance either way
+        assert_ne!(first.years, second.years);
+    }
+
+    #[test]
+    fn compounding_matches_the_formula() {
+        let mut annual = Portfolio::new();
+        annual.add(account("CD", 1_000.0, 0.05, Frequency::Annually));
+        assert_close(annual.project(10).rows[10].total(), 1_000.0 * 1.05f64.powi(10)); // 1628.89
+
+        let mut monthly = Portfolio::new();
+        monthly.add(account("Savings", 1_000.0, 0.12, Frequency::Monthly));
+        let row = &monthly.project(1).rows[1];
+        assert_close(row.total(), 1_000.0 * 1.01f64.powi(12)); // 1126.83
+        assert_close(row.interest, 1_000.0 * 1.01f64.powi(12) - 1_000.0);
+    }
+
+    #[test]
+    fn no_volatility_means_every_trial_matches_the_projection() {
+        let mut steady = Portfolio::new();
+        steady.add(account("CD", 1_000.0, 0.05, Frequency::Annually));
+        let expected = steady.project(5).rows[5].total();
+        let simulation = steady.monte_carlo(5, 20, 7);
+        let last = &simulation.years[5];
+        for value in [last.p10, last.p50, last.p90] {
+            assert_close(value, expected);
+        }
+    }
+
+    #[test]
+    fn contributions_are_spread_across_periods() {
+        let mut flat = Portfolio::new();
+        flat.add(account("Cash", 0.0, 0.0, Frequency::Quarterly).with_contribution(1_200.0).unwrap());
+        let rows = flat.project(3).rows;
+        assert_eq!(rows.iter().map(YearRow::total).collect::<Vec<f64>>(), vec![0.0, 1_200.0, 2_400.0, 3_600.0]);
+        assert_close(rows[2].contributed, 1_200.0);
+
+        // Each month earns 1% and then gets 100 more, an ordinary annuity
+        let mut growing = Portfolio::new();
+        growing.add(account("Savings", 0.0, 0.12, Frequency::Monthly).with_contribution(1_200.0).unwrap());
+        let row = &growing.project(1).rows[1];
+        assert_close(row.total(), 100.0 * (1.01f64.powi(12) - 1.0) / 0.01); // 1268.25
+        assert_close(row.interest, row.total() - 1_200.0);
+    }
+
+    #[test]
+    fn withdrawals_stop_when_the_account_runs_dry() {
+        let mut drawdown = Portfolio::new();
+        drawdown.add(account("Savings", 1_000.0, 0.0, Frequency::Annually).with_withdrawal(400.0).unwrap());
+        let rows = drawdown.project(4).rows;
+        assert_eq!(rows.iter().map(YearRow::total).collect::<Vec<f64>>(), vec![1_000.0, 600.0, 200.0, 0.0, 0.0]);
+        assert_eq!(rows.iter().map(|row| row.withdrawn).collect::<Vec<f64>>(), vec![0.0, 400.0, 400.0, 200.0, 0.0]);
+
+        assert_eq!(drawdown.monte_carlo(4, 25, 1).depleted, 25);
+        assert_eq!(drawdown.monte_carlo(2, 25, 1).depleted, 0);
+    }
+
+    #[test]
+    fn accounts_reject_invalid_settings() {
+        assert_eq!(Account::new("Bad", -1.0, 0.05, Frequency::Annually), Err(PortfolioError::InvalidBalance(-1.0)));
+        assert!(matches!(Account::new("Bad", f64::NAN, 0.05, Frequency::Annually), Err(PortfolioError::InvalidBalance(_))));
+        assert_eq!(Account::new("Bad", 1.0, -1.0, Frequency::Annually), Err(PortfolioError::InvalidRate(-1.0)));
+        assert!(matches!(Account::new("Bad", 1.0, f64::NAN, Frequency::Annually), Err(PortfolioError::InvalidRate(_))));
+        assert!(Account::new("Falling", 1.0, -0.2, Frequency::Annually).is_ok());
+
+        let cd = account("CD", 1_000.0, 0.05, Frequency::Annually);
+        assert_eq!(cd.clone().with_contribution(-5.0), Err(PortfolioError::InvalidBalance(-5.0)));
+        assert_eq!(cd.clone().with_withdrawal(f64::INFINITY), Err(PortfolioError::InvalidBalance(f64::INFINITY)));
+        assert_eq!(cd.clone().with_volatility(-0.1), Err(PortfolioError::InvalidRate(-0.1)));
+        assert!(matches!(cd.with_volatility(f64::NAN), Err(PortfolioError::InvalidRate(_))));
+    }
+}
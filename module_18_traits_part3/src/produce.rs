@@ -0,0 +1,353 @@
+// A fruit stand's apple inventory. Stock arrives in batches with a variety, weight, harvest date and grade, and each batch
+// expires once its shelf life runs out. Selling takes from the oldest unexpired batches first (FIFO), and the spoilage
+// report lists what has gone bad or is about to. Everything about a variety comes from AppleType::info, so a new variety is
+// one enum variant plus one row there.
+use std::fmt::{Display, Formatter};
+
+use crate::AppleType;
+use crate::money::{Currency, Money, MoneyError, Rounding};
+
+pub struct VarietyInfo {
+    pub name: &'static str,
+    pub shelf_life_days: u32, // For Extra Fancy stock, lower grades keep for less
+    pub price_per_kg: Money,
+}
+
+impl AppleType {
+    pub fn info(&self) -> &'static VarietyInfo {
+        const fn variety(name: &'static str, shelf_life_days: u32, cents_per_kg: i64) -> VarietyInfo {
+            VarietyInfo { name, shelf_life_days, price_per_kg: Money::from_minor(cents_per_kg, Currency::Usd) }
+        }
+        const RED_DELICIOUS: VarietyInfo = variety("Red Delicious", 60, 420);
+        const GRANNY_SMITH: VarietyInfo = variety("Granny Smith", 90, 460);
+        const HONEYCRISP: VarietyInfo = variety("Honeycrisp", 45, 650);
+        const FUJI: VarietyInfo = variety("Fuji", 75, 500);
+        match self {
+            AppleType::RedDelicious => &RED_DELICIOUS,
+            AppleType::GrannySmith => &GRANNY_SMITH,
+            AppleType::Honeycrisp => &HONEYCRISP,
+            AppleType::Fuji => &FUJI,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    ExtraFancy,
+    Fancy,
+    Utility, // Blemished, sold cheap for baking and keeps the least time
+}
+
+impl Grade {
+    // (shelf life percent, price percent)
+    fn factors(&self) -> (u32, i64) {
+        match self {
+            Grade::ExtraFancy => (100, 120),
+            Grade::Fancy => (90, 100),
+            Grade::Utility => (50, 60),
+        }
+    }
+}
+
+// A calendar date, stored as days since 1970-01-01 so dates can be compared and subtracted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(i64);
+
+impl Date {
+    pub fn new(year: i64, month: u32, day: u32) -> Option<Self> {
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+            2 => 28,
+            _ => return None,
+        };
+        if day == 0 || day > days_in_month {
+            return None;
+        }
+        // Howard Hinnant's days_from_civil
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month_index = (month as i64 + 9) % 12; // March is 0
+        let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Some(Date(era * 146097 + day_of_era - 719468))
+    }
+
+    // "2024-09-15"
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        Self::new(year, month, day)
+    }
+
+    pub fn add_days(&self, days: i64) -> Self {
+        Date(self.0 + days)
+    }
+
+    pub fn days_until(&self, later: Date) -> i64 {
+        later.0 - self.0
+    }
+
+    fn to_civil(self) -> (i64, u32, u32) {
+        let z = self.0 + 719468;
+        let era = z.div_euclid(146097);
+        let day_of_era = z - era * 146097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, day)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = self.to_civil();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BatchId(u32);
+
+impl Display for BatchId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "B{:03}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    pub id: BatchId,
+    pub variety: AppleType,
+    pub grams: u32,
+    pub harvested: Date,
+    pub grade: Grade,
+}
+
+impl Batch {
+    pub fn expires(&self) -> Date {
+        let (shelf_percent, _) = self.grade.factors();
+        self.harvested.add_days((self.variety.info().shelf_life_days * shelf_percent / 100) as i64)
+    }
+
+    // Sold on the expiry date is fine, the day after is not
+    pub fn is_expired(&self, today: Date) -> bool {
+        today > self.expires()
+    }
+
+    pub fn price_of(&self, grams: u32) -> Result<Money, MoneyError> {
+        let (_, price_percent) = self.grade.factors();
+        self.variety.info().price_per_kg.mul_ratio(grams as i64 * price_percent, 1000 * 100, Rounding::HalfUp)
+    }
+
+    pub fn value(&self) -> Result<Money, MoneyError> {
+        self.price_of(self.grams)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StandError {
+    NoWeight,
+    NotEnough { variety: AppleType, wanted: u32, available: u32 }, // Counting unexpired stock only
+    Money(MoneyError),
+}
+
+impl Display for StandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StandError::NoWeight => write!(f, "The weight has to be more than 0g"),
+            StandError::NotEnough { variety, wanted, available } => {
+                write!(f, "Wanted {}g of {} but only {}g is fresh", wanted, variety, available)
+            }
+            StandError::Money(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<MoneyError> for StandError {
+    fn from(error: MoneyError) -> Self {
+        StandError::Money(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sale {
+    pub variety: AppleType,
+    pub picks: Vec<(BatchId, u32)>, // Grams taken from each batch, oldest first
+    pub price: Money,
+}
+
+impl Display for Sale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let picks: Vec<String> = self.picks.iter().map(|(id, grams)| format!("{}g from {}", grams, id)).collect();
+        write!(f, "Sold {}: {} --> {}", self.variety, picks.join(", "), self.price)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpoilageReport {
+    pub today: Date,
+    pub expired: Vec<Batch>,
+    pub expiring: Vec<Batch>, // Still sellable, but not for long
+    pub value_lost: Money,
+}
+
+impl Display for SpoilageReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "** Spoilage report for {} **", self.today)?;
+        for (label, batches) in [("Expired", &self.expired), ("Expiring", &self.expiring)] {
+            for batch in batches {
+                writeln!(
+                    f,
+                    "{:<9} {} {:<14} {:>6}g {:?}, harvested {}, expires {}",
+                    label,
+                    batch.id,
+                    batch.variety,
+                    batch.grams,
+                    batch.grade,
+                    batch.harvested,
+                    batch.expires()
+                )?;
+            }
+        }
+        write!(f, "Value lost to spoilage: {}", self.value_lost)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FruitStand {
+    batches: Vec<Batch>, // Oldest harvest first
+    next_id: u32,
+}
+
+impl FruitStand {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn receive(&mut self, variety: AppleType, grams: u32, harvested: Date, grade: Grade) -> Result<BatchId, StandError> {
+        if grams == 0 {
+            return Err(StandError::NoWeight);
+        }
+        self.next_id += 1;
+        let id = BatchId(self.next_id);
+        let at = self.batches.partition_point(|batch| batch.harvested <= harvested);
+        self.batches.insert(at, Batch { id, variety, grams, harvested, grade });
+        Ok(id)
+    }
+
+    // Stops at u32::MAX, which is still enough to tell whether any order can be filled
+    pub fn fresh_grams(&self, variety: AppleType, today: Date) -> u32 {
+        self.fresh(variety, today).fold(0, |total, batch| total.saturating_add(batch.grams))
+    }
+
+    fn fresh(&self, variety: AppleType, today: Date) -> impl Iterator<Item = &Batch> {
+        self.batches.iter().filter(move |batch| batch.variety == variety && !batch.is_expired(today))
+    }
+
+    // Takes from the oldest fresh batches of the variety first. Nothing is taken unless the whole order can be filled.
+    pub fn sell(&mut self, variety: AppleType, grams: u32, today: Date) -> Result<Sale, StandError> {
+        if grams == 0 {
+            return Err(StandError::NoWeight);
+        }
+        let available = self.fresh_grams(variety, today);
+        if available < grams {
+            return Err(StandError::NotEnough { variety, wanted: grams, available });
+        }
+
+        // Work out the picks and the price first, so an error leaves every batch as it was
+        let mut sale = Sale { variety, picks: Vec::new(), price: Money::zero(Currency::Usd) };
+        let mut remaining = grams;
+        for batch in self.fresh(variety, today) {
+            if remaining == 0 {
+                break;
+            }
+            let taken = remaining.min(batch.grams);
+            sale.price = sale.price.checked_add(batch.price_of(taken)?)?;
+            sale.picks.push((batch.id, taken));
+            remaining -= taken;
+        }
+        for (id, taken) in &sale.picks {
+            if let Some(batch) = self.batches.iter_mut().find(|batch| batch.id == *id) {
+                batch.grams -= taken;
+            }
+        }
+        self.batches.retain(|batch| batch.grams > 0);
+        Ok(sale)
+    }
+
+    pub fn spoilage_report(&self, today: Date, warning_days: i64) -> Result<SpoilageReport, StandError> {
+        let expired: Vec<Batch> = self.batches.iter().filter(|batch| batch.is_expired(today)).cloned().collect();
+        let expiring = self
+            .batches
+            .iter()
+            .filter(|batch| !batch.is_expired(today) && today.days_until(batch.expires()) <= warning_days)
+            .cloned()
+            .collect();
+        let mut value_lost = Money::zero(Currency::Usd);
+        for batch in &expired {
+            value_lost = value_lost.checked_add(batch.value()?)?;
+        }
+        Ok(SpoilageReport { today, expired, expiring, value_lost })
+    }
+
+    // Clears expired batches off the shelf and hands them back
+    pub fn discard_expired(&mut self, today: Date) -> Vec<Batch> {
+        let (expired, fresh) = self.batches.drain(..).partition(|batch| batch.is_expired(today));
+        self.batches = fresh;
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> Date {
+        Date::parse(text).unwrap()
+    }
+
+    #[test]
+    fn sells_oldest_fresh_stock_first() {
+        let mut stand = FruitStand::new();
+        let old = stand.receive(AppleType::Fuji, 1000, date("2024-01-01"), Grade::Fancy).unwrap(); // Expired
+        let first = stand.receive(AppleType::Fuji, 1000, date("2024-09-01"), Grade::Fancy).unwrap();
+        let second = stand.receive(AppleType::Fuji, 1000, date("2024-09-10"), Grade::Fancy).unwrap();
+        let sale = stand.sell(AppleType::Fuji, 1500, date("2024-10-01")).unwrap();
+        assert_eq!(sale.picks, [(first, 1000), (second, 500)]);
+        assert_eq!(sale.price, Money::from_minor(750, Currency::Usd));
+        let left: Vec<(BatchId, u32)> = stand.batches.iter().map(|batch| (batch.id, batch.grams)).collect();
+        assert_eq!(left, [(old, 1000), (second, 500)]);
+    }
+
+    #[test]
+    fn failed_sale_takes_nothing() {
+        let mut stand = FruitStand::new();
+        stand.receive(AppleType::Fuji, 1000, date("2024-09-01"), Grade::Fancy).unwrap();
+        let before = stand.batches.clone();
+        assert_eq!(
+            stand.sell(AppleType::Fuji, 1001, date("2024-10-01")),
+            Err(StandError::NotEnough { variety: AppleType::Fuji, wanted: 1001, available: 1000 })
+        );
+        assert_eq!(stand.batches, before);
+    }
+
+    #[test]
+    fn huge_stock_doesnt_overflow() {
+        let mut stand = FruitStand::new();
+        stand.receive(AppleType::Fuji, 3_000_000_000, date("2024-09-01"), Grade::Fancy).unwrap();
+        stand.receive(AppleType::Fuji, 3_000_000_000, date("2024-09-02"), Grade::Fancy).unwrap();
+        let today = date("2024-10-01");
+        assert_eq!(stand.fresh_grams(AppleType::Fuji, today), u32::MAX);
+        let sale = stand.sell(AppleType::Fuji, 4_000_000_000, today).unwrap();
+        assert_eq!(sale.picks.iter().map(|(_, grams)| *grams as u64).sum::<u64>(), 4_000_000_000);
+        assert_eq!(stand.fresh_grams(AppleType::Fuji, today), 2_000_000_000);
+    }
+}
@@ -0,0 +1,216 @@
+// A day's appointments for each doctor. Appointment keeps its times as strings, so everything is parsed into Clock times on
+// the way in and rejected if it ends before it starts, falls outside the doctor's working hours or overlaps another
+// booking. Bookings are half-open, so one can start the minute the previous one ends.
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use crate::clock::{Clock, ClockError};
+use crate::{Appointment, Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BookingId(u32);
+
+impl Display for BookingId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Booking {
+    pub id: BookingId,
+    pub start: Clock,
+    pub end: Clock,
+}
+
+impl Booking {
+    fn overlaps(&self, start: Clock, end: Clock) -> bool {
+        start < self.end && self.start < end
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduleError {
+    Time(ClockError),
+    EndsBeforeStart { start: Clock, end: Clock },
+    OutsideHours { open: Clock, close: Clock },
+    Conflict(Booking),
+    UnknownBooking(BookingId),
+    BookedOutsideHours(Booking), // Changing the hours would strand an existing booking
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::Time(error) => write!(f, "{}", error),
+            ScheduleError::EndsBeforeStart { start, end } => write!(f, "An appointment can't end at {} if it starts at {}", end, start),
+            ScheduleError::OutsideHours { open, close } => write!(f, "The doctor only sees patients from {} to {}", open, close),
+            ScheduleError::Conflict(booking) => write!(f, "Overlaps appointment {} from {} to {}", booking.id, booking.start, booking.end),
+            ScheduleError::UnknownBooking(id) => write!(f, "There is no appointment {}", id),
+            ScheduleError::BookedOutsideHours(booking) => {
+                write!(f, "Appointment {} from {} to {} would be outside the new hours", booking.id, booking.start, booking.end)
+            }
+        }
+    }
+}
+
+impl From<ClockError> for ScheduleError {
+    fn from(error: ClockError) -> Self {
+        ScheduleError::Time(error)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DoctorDay {
+    open: Clock,
+    close: Clock,
+    bookings: Vec<Booking>, // Sorted by start time
+}
+
+impl DoctorDay {
+    fn check(&self, start: Clock, end: Clock, ignoring: Option<BookingId>) -> Result<(), ScheduleError> {
+        if start >= end {
+            return Err(ScheduleError::EndsBeforeStart { start, end });
+        }
+        if start < self.open || end > self.close {
+            return Err(ScheduleError::OutsideHours { open: self.open, close: self.close });
+        }
+        match self.bookings.iter().find(|booking| Some(booking.id) != ignoring && booking.overlaps(start, end)) {
+            Some(booking) => Err(ScheduleError::Conflict(*booking)),
+            None => Ok(()),
+        }
+    }
+
+    fn insert(&mut self, booking: Booking) {
+        let at = self.bookings.partition_point(|existing| existing.start <= booking.start);
+        self.bookings.insert(at, booking);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    default_open: Clock,
+    default_close: Clock,
+    doctors: HashMap<String, DoctorDay>,
+    next_id: u32,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self { default_open: Clock::hm(9, 0), default_close: Clock::hm(17, 0), doctors: HashMap::new(), next_id: 1 }
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Fails without changing anything if an existing booking wouldn't fit in the new hours
+    pub fn set_hours(&mut self, doctor: &str, open: &str, close: &str) -> Result<(), ScheduleError> {
+        let (open, close) = (Clock::parse(open)?, Clock::parse(close)?);
+        if open >= close {
+            return Err(ScheduleError::EndsBeforeStart { start: open, end: close });
+        }
+        if let Some(booking) = self.bookings(doctor).iter().find(|booking| booking.start < open || booking.end > close) {
+            return Err(ScheduleError::BookedOutsideHours(*booking));
+        }
+        let day = self.day_mut(doctor);
+        day.open = open;
+        day.close = close;
+        Ok(())
+    }
+
+    fn day_mut(&mut self, doctor: &str) -> &mut DoctorDay {
+        let (open, close) = (self.default_open, self.default_close);
+        self.doctors.entry(doctor.to_string()).or_insert_with(|| DoctorDay { open, close, bookings: Vec::new() })
+    }
+
+    pub fn book(&mut self, appointment: &Appointment) -> Result<BookingId, ScheduleError> {
+        let (start, end) = (Clock::parse(&appointment.start_time)?, Clock::parse(&appointment.end_time)?);
+        let id = BookingId(self.next_id);
+        let day = self.day_mut(&appointment.doctor);
+        day.check(start, end, None)?;
+        day.insert(Booking { id, start, end });
+        self.next_id += 1;
+        Ok(id)
+    }
+
+    // Moves a booking to a new start time, keeping its length. The old slot stays booked if the new one doesn't fit.
+    pub fn reschedule(&mut self, doctor: &str, id: BookingId, new_start: &str) -> Result<Booking, ScheduleError> {
+        let start = Clock::parse(new_start)?;
+        let day = self.doctors.get_mut(doctor).ok_or(ScheduleError::UnknownBooking(id))?;
+        let at = day.bookings.iter().position(|booking| booking.id == id).ok_or(ScheduleError::UnknownBooking(id))?;
+        let length = day.bookings[at].start.minutes_until(day.bookings[at].end).unwrap_or(0);
+        let end = start.add_minutes(length);
+        day.check(start, end, Some(id))?;
+        day.bookings.remove(at);
+        let booking = Booking { id, start, end };
+        day.insert(booking);
+        Ok(booking)
+    }
+
+    pub fn cancel(&mut self, doctor: &str, id: BookingId) -> Result<Booking, ScheduleError> {
+        let day = self.doctors.get_mut(doctor).ok_or(ScheduleError::UnknownBooking(id))?;
+        let at = day.bookings.iter().position(|booking| booking.id == id).ok_or(ScheduleError::UnknownBooking(id))?;
+        Ok(day.bookings.remove(at))
+    }
+
+    // The earliest gap of at least the given length starting no sooner than `after`, or None if the day is full or the
+    // length is longer than the whole working day
+    pub fn next_free_slot(&self, doctor: &str, length: Duration, after: &str) -> Result<Option<(Clock, Clock)>, ScheduleError> {
+        let after = Clock::parse(after)?;
+        let (open, close, bookings) = match self.doctors.get(doctor) {
+            Some(day) => (day.open, day.close, day.bookings.as_slice()),
+            None => (self.default_open, self.default_close, &[][..]),
+        };
+        let minutes = length.total_seconds().div_ceil(60); // Bookings are to the minute
+        let Some(minutes) = u32::try_from(minutes).ok().filter(|&minutes| minutes <= open.minutes_until(close).unwrap_or(0)) else {
+            return Ok(None);
+        };
+        let mut candidate = after.max(open);
+        for booking in bookings {
+            if booking.end <= candidate {
+                continue;
+            }
+            if candidate.checked_add_minutes(minutes).is_some_and(|end| end <= booking.start) {
+                break;
+            }
+            candidate = booking.end;
+        }
+        Ok(candidate.checked_add_minutes(minutes).filter(|&end| end <= close).map(|end| (candidate, end)))
+    }
+
+    pub fn bookings(&self, doctor: &str) -> &[Booking] {
+        self.doctors.get(doctor).map_or(&[], |day| day.bookings.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_longer_than_the_day_are_none() {
+        let mut schedule = Scheduler::new();
+        schedule.book(&Appointment::new("Dr. Lee", "9:00 AM", "10:00 AM")).unwrap();
+        let slot = |length| schedule.next_free_slot("Dr. Lee", length, "8:00 AM").unwrap();
+        assert_eq!(slot(Duration::new(7, 0, 0)), Some((Clock::hm(10, 0), Clock::hm(17, 0))));
+        assert_eq!(slot(Duration::new(8, 0, 0)), None); // The whole day, but 9 to 10 is taken
+        assert_eq!(slot(Duration::new(71_582_789, 0, 0)), None); // Just over u32::MAX minutes
+        assert_eq!(slot(Duration::new(u32::MAX, 59, 59)), None);
+    }
+
+    #[test]
+    fn hours_cant_shrink_past_a_booking() {
+        let mut schedule = Scheduler::new();
+        let id = schedule.book(&Appointment::new("Dr. Lee", "4:00 PM", "5:00 PM")).unwrap();
+        let booking = schedule.bookings("Dr. Lee")[0];
+        assert_eq!(schedule.set_hours("Dr. Lee", "9:00 AM", "4:30 PM"), Err(ScheduleError::BookedOutsideHours(booking)));
+        assert_eq!(schedule.set_hours("Dr. Lee", "4:30 PM", "6:00 PM"), Err(ScheduleError::BookedOutsideHours(booking)));
+        assert!(schedule.book(&Appointment::new("Dr. Lee", "8:00 AM", "8:30 AM")).is_err()); // Still 9 to 5
+        schedule.set_hours("Dr. Lee", "8:00 AM", "5:00 PM").unwrap();
+        schedule.cancel("Dr. Lee", id).unwrap();
+        schedule.set_hours("Dr. Lee", "8:00 AM", "12:00 PM").unwrap();
+    }
+}
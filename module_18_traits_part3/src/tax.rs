@@ -0,0 +1,406 @@
+// A yearly tax return built on the Investment and Taxable traits. Taxable's flat TAX_RATE is what gets withheld from each
+// item during the year, while the liability itself comes from progressive brackets for the filer's status. The difference
+// between the two is the refund or the amount owed. Bracket tables are read from a file so a new tax year needs no code change.
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use crate::{Investment, Taxable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilingStatus {
+    Single,
+    MarriedJoint,
+    HeadOfHousehold,
+}
+
+impl FilingStatus {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.to_lowercase().as_str() {
+            "single" => Some(FilingStatus::Single),
+            "married_joint" => Some(FilingStatus::MarriedJoint),
+            "head_of_household" => Some(FilingStatus::HeadOfHousehold),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FilingStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilingStatus::Single => write!(f, "Single"),
+            FilingStatus::MarriedJoint => write!(f, "Married filing jointly"),
+            FilingStatus::HeadOfHousehold => write!(f, "Head of household"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bracket {
+    pub floor: f64,         // Income above this is taxed at rate, up to the next bracket's floor
+    pub ceiling: Option<f64>, // None for the top bracket
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub standard_deduction: f64,
+    pub brackets: Vec<Bracket>,
+}
+
+#[derive(Debug)]
+pub enum TaxError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+    InvalidSchedule { status: FilingStatus, message: String },
+    NoSchedule(FilingStatus),
+    InvalidDeduction { label: String, amount: f64 },
+}
+
+impl Display for TaxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaxError::Io(error) => write!(f, "Could not read bracket table: {}", error),
+            TaxError::Parse { line, message } => write!(f, "Bracket table line {}: {}", line, message),
+            TaxError::InvalidSchedule { status, message } => write!(f, "Bracket table for {}: {}", status, message),
+            TaxError::NoSchedule(status) => write!(f, "The bracket table has no schedule for {}", status),
+            TaxError::InvalidDeduction { label, amount } => write!(f, "{} is not a valid amount for '{}'", amount, label),
+        }
+    }
+}
+
+impl From<std::io::Error> for TaxError {
+    fn from(error: std::io::Error) -> Self {
+        TaxError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TaxTable {
+    schedules: HashMap<FilingStatus, Schedule>,
+}
+
+impl TaxTable {
+    // One row per line: "status | deduction | amount" or "status | bracket | floor | rate percent". Brackets can be listed
+    // in any order, but every status needs a bracket starting at 0 and a standard deduction.
+    pub fn parse(text: &str) -> Result<Self, TaxError> {
+        let mut deductions: HashMap<FilingStatus, (f64, usize)> = HashMap::new(); // Amount and the line it was on
+        let mut floors: HashMap<FilingStatus, Vec<(f64, f64)>> = HashMap::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parse_error = |message: String| TaxError::Parse { line: index + 1, message };
+            let number = |column: &str| -> Result<f64, TaxError> {
+                match column.parse::<f64>() {
+                    Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+                    _ => Err(parse_error(format!("'{}' is not a non-negative number", column))),
+                }
+            };
+
+            let columns: Vec<&str> = line.split('|').map(str::trim).collect();
+            let status = FilingStatus::parse(columns[0]).ok_or_else(|| parse_error(format!("unknown filing status '{}'", columns[0])))?;
+            match (columns.get(1).map(|kind| kind.to_lowercase()).as_deref(), columns.len()) {
+                (Some("deduction"), 3) => {
+                    if deductions.insert(status, (number(columns[2])?, index + 1)).is_some() {
+                        return Err(parse_error(format!("second standard deduction for {}", status)));
+                    }
+                }
+                (Some("bracket"), 4) => {
+                    let rate = number(columns[3])? / 100.0;
+                    if rate > 1.0 {
+                        return Err(parse_error(format!("rate {}% is over 100%", columns[3])));
+                    }
+                    floors.entry(status).or_default().push((number(columns[2])?, rate));
+                }
+                _ => return Err(parse_error(String::from("expected 'status | deduction | amount' or 'status | bracket | floor | rate'"))),
+            }
+        }
+
+        // A deduction with no brackets would silently leave the status without a schedule
+        let orphan = deductions.iter().filter(|(status, _)| !floors.contains_key(status)).min_by_key(|(_, (_, line))| *line);
+        if let Some((status, &(_, line))) = orphan {
+            return Err(TaxError::Parse { line, message: format!("standard deduction for {} but no brackets", status) });
+        }
+
+        let mut schedules = HashMap::new();
+        for (status, mut rows) in floors {
+            rows.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let invalid = |message: &str| TaxError::InvalidSchedule { status, message: message.to_string() };
+            if rows[0].0 != 0.0 {
+                return Err(invalid("the lowest bracket must start at 0"));
+            }
+            if rows.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+                return Err(invalid("two brackets start at the same income"));
+            }
+            let standard_deduction = deductions.get(&status).ok_or_else(|| invalid("no standard deduction"))?.0;
+            let brackets = rows
+                .iter()
+                .enumerate()
+                .map(|(i, &(floor, rate))| Bracket { floor, ceiling: rows.get(i + 1).map(|next| next.0), rate })
+                .collect();
+            schedules.insert(status, Schedule { standard_deduction, brackets });
+        }
+        Ok(Self { schedules })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TaxError> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    pub fn schedule(&self, status: FilingStatus) -> Result<&Schedule, TaxError> {
+        self.schedules.get(&status).ok_or(TaxError::NoSchedule(status))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReturnItem {
+    pub label: String,
+    pub amount: f64,
+    pub withheld: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxReturn {
+    pub status: FilingStatus,
+    items: Vec<ReturnItem>,
+    deductions: Vec<(String, f64)>, // Itemized, only used when they beat the standard deduction
+}
+
+impl TaxReturn {
+    pub fn new(status: FilingStatus) -> Self {
+        Self { status, items: Vec::new(), deductions: Vec::new() }
+    }
+
+    // Anything Taxable had its flat TAX_RATE withheld during the year
+    pub fn add_taxable<T: Taxable>(&mut self, label: &str, item: &T) {
+        self.items.push(ReturnItem { label: label.to_string(), amount: item.get_amount(), withheld: item.tax_bill() });
+    }
+
+    // Other investment income that nothing was withheld from
+    pub fn add_investment<I: Investment<f64>>(&mut self, label: &str, item: &I) {
+        self.items.push(ReturnItem { label: label.to_string(), amount: item.get_amount(), withheld: 0.0 });
+    }
+
+    pub fn itemize(&mut self, label: &str, amount: f64) -> Result<(), TaxError> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(TaxError::InvalidDeduction { label: label.to_string(), amount });
+        }
+        self.deductions.push((label.to_string(), amount));
+        Ok(())
+    }
+
+    pub fn compute(&self, table: &TaxTable) -> Result<TaxSummary, TaxError> {
+        let schedule = table.schedule(self.status)?;
+        let gross_income: f64 = self.items.iter().map(|item| item.amount).sum();
+        let itemized: f64 = self.deductions.iter().map(|(_, amount)| amount).sum();
+        let (deduction_label, deduction) = if itemized > schedule.standard_deduction {
+            ("Itemized", itemized)
+        } else {
+            ("Standard", schedule.standard_deduction)
+        };
+        let taxable_income = (gross_income - deduction).max(0.0);
+
+        let breakdown: Vec<BracketLine> = schedule
+            .brackets
+            .iter()
+            .filter(|bracket| taxable_income > bracket.floor)
+            .map(|bracket| {
+                let top = bracket.ceiling.map_or(taxable_income, |ceiling| ceiling.min(taxable_income));
+                let taxed = top - bracket.floor;
+                BracketLine { bracket: *bracket, taxed, tax: taxed * bracket.rate }
+            })
+            .collect();
+
+        Ok(TaxSummary {
+            status: self.status,
+            items: self.items.clone(),
+            gross_income,
+            deduction_label,
+            deduction,
+            taxable_income,
+            tax: breakdown.iter().map(|line| line.tax).sum(),
+            withheld: self.items.iter().map(|item| item.withheld).sum(),
+            breakdown,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BracketLine {
+    pub bracket: Bracket,
+    pub taxed: f64, // The slice of taxable income that fell in this bracket
+    pub tax: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxSummary {
+    pub status: FilingStatus,
+    pub items: Vec<ReturnItem>,
+    pub gross_income: f64,
+    pub deduction_label: &'static str,
+    pub deduction: f64,
+    pub taxable_income: f64,
+    pub breakdown: Vec<BracketLine>,
+    pub tax: f64,
+    pub withheld: f64,
+}
+
+impl TaxSummary {
+    pub fn effective_rate(&self) -> f64 {
+        if self.gross_income <= 0.0 { 0.0 } else { self.tax / self.gross_income }
+    }
+
+    pub fn marginal_rate(&self) -> f64 {
+        self.breakdown.last().map_or(0.0, |line| line.bracket.rate)
+    }
+
+    // Positive when the filer gets money back, negative when they owe
+    pub fn refund(&self) -> f64 {
+        self.withheld - self.tax
+    }
+}
+
+impl Display for TaxSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "** Tax Return ({}) **", self.status)?;
+        for item in &self.items {
+            writeln!(f, "  {:<17} ${:>12.2} (${:.2} withheld)", item.label, item.amount, item.withheld)?;
+        }
+        writeln!(f, "Gross income:       ${:>12.2}", self.gross_income)?;
+        writeln!(f, "{} deduction: ${:>12.2}", self.deduction_label, self.deduction)?;
+        writeln!(f, "Taxable income:     ${:>12.2}", self.taxable_income)?;
+        for line in &self.breakdown {
+            let range = match line.bracket.ceiling {
+                Some(ceiling) => format!("${:.0} to ${:.0}", line.bracket.floor, ceiling),
+                None => format!("${:.0} and up", line.bracket.floor),
+            };
+            writeln!(f, "  {:>4.1}% on {:<22} ${:>12.2} taxed --> ${:>10.2}", line.bracket.rate * 100.0, range, line.taxed, line.tax)?;
+        }
+        writeln!(f, "Total tax:          ${:>12.2}", self.tax)?;
+        writeln!(f, "Effective rate: {:.2}%, marginal rate: {:.0}%", self.effective_rate() * 100.0, self.marginal_rate() * 100.0)?;
+        writeln!(f, "Withheld:           ${:>12.2}", self.withheld)?;
+        if self.refund() >= 0.0 {
+            write!(f, "Refund:             ${:>12.2}", self.refund())
+        } else {
+            write!(f, "Amount owed:        ${:>12.2}", -self.refund())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = "\
+# Two statuses, brackets deliberately out of order
+single | deduction | 10000
+single | bracket | 40000 | 20
+single | bracket | 0 | 10
+single | bracket | 100000 | 30
+married_joint | deduction | 20000
+married_joint | bracket | 0 | 10
+";
+
+    struct Wage(f64);
+
+    impl Investment<f64> for Wage {
+        fn get_amount(&self) -> f64 {
+            self.0
+        }
+        fn double_amount(&mut self) {
+            self.0 *= 2.0;
+        }
+    }
+
+    impl Taxable for Wage {}
+
+    fn line_of(error: TaxError) -> usize {
+        match error {
+            TaxError::Parse { line, .. } => line,
+            other => panic!("expected a parse error, got {}", other),
+        }
+    }
+
+    #[test]
+    fn parse_sorts_brackets_and_links_ceilings() {
+        let table = TaxTable::parse(TABLE).unwrap();
+        let single = table.schedule(FilingStatus::Single).unwrap();
+        assert_eq!(single.standard_deduction, 10000.0);
+        let ranges: Vec<(f64, Option<f64>, f64)> = single.brackets.iter().map(|b| (b.floor, b.ceiling, b.rate)).collect();
+        assert_eq!(ranges, vec![(0.0, Some(40000.0), 0.1), (40000.0, Some(100000.0), 0.2), (100000.0, None, 0.3)]);
+        assert!(matches!(table.schedule(FilingStatus::HeadOfHousehold), Err(TaxError::NoSchedule(FilingStatus::HeadOfHousehold))));
+    }
+
+    #[test]
+    fn parse_rejects_bad_lines() {
+        assert_eq!(line_of(TaxTable::parse("\nwidowed | deduction | 100").unwrap_err()), 2);
+        assert_eq!(line_of(TaxTable::parse("single | bracket | 0").unwrap_err()), 1);
+        assert_eq!(line_of(TaxTable::parse("single | bracket | 0 | -5").unwrap_err()), 1);
+        assert_eq!(line_of(TaxTable::parse("single | bracket | 0 | 150").unwrap_err()), 1);
+        assert_eq!(line_of(TaxTable::parse("single | deduction | NaN").unwrap_err()), 1);
+        assert_eq!(line_of(TaxTable::parse("single | deduction | 1\nsingle | deduction | 2").unwrap_err()), 2);
+    }
+
+    #[test]
+    fn parse_rejects_a_deduction_without_brackets() {
+        let text = "single | deduction | 10000\nsingle | bracket | 0 | 10\nhead_of_household | deduction | 15000";
+        assert_eq!(line_of(TaxTable::parse(text).unwrap_err()), 3);
+    }
+
+    #[test]
+    fn parse_rejects_invalid_schedules() {
+        let no_zero = TaxTable::parse("single | deduction | 1\nsingle | bracket | 10 | 10");
+        assert!(matches!(no_zero, Err(TaxError::InvalidSchedule { status: FilingStatus::Single, .. })));
+        let duplicate = TaxTable::parse("single | deduction | 1\nsingle | bracket | 0 | 10\nsingle | bracket | 0 | 20");
+        assert!(matches!(duplicate, Err(TaxError::InvalidSchedule { .. })));
+        let no_deduction = TaxTable::parse("single | bracket | 0 | 10");
+        assert!(matches!(no_deduction, Err(TaxError::InvalidSchedule { .. })));
+    }
+
+    #[test]
+    fn compute_taxes_each_bracket_slice() {
+        let table = TaxTable::parse(TABLE).unwrap();
+        let mut filing = TaxReturn::new(FilingStatus::Single);
+        filing.add_taxable("Salary", &Wage(130000.0));
+        let summary = filing.compute(&table).unwrap();
+        assert_eq!(summary.deduction_label, "Standard");
+        assert_eq!(summary.taxable_income, 120000.0);
+        let slices: Vec<f64> = summary.breakdown.iter().map(|line| line.taxed).collect();
+        assert_eq!(slices, vec![40000.0, 60000.0, 20000.0]);
+        assert!((summary.tax - (4000.0 + 12000.0 + 6000.0)).abs() < 1e-9);
+        assert_eq!(summary.marginal_rate(), 0.3);
+        assert!((summary.refund() - (summary.withheld - 22000.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compute_uses_the_larger_deduction_and_never_goes_negative() {
+        let table = TaxTable::parse(TABLE).unwrap();
+        let mut filing = TaxReturn::new(FilingStatus::MarriedJoint);
+        filing.add_taxable("Salary", &Wage(50000.0));
+        filing.itemize("Mortgage interest", 15000.0).unwrap();
+        assert_eq!(filing.compute(&table).unwrap().deduction_label, "Standard");
+        filing.itemize("Charitable giving", 10000.0).unwrap();
+        let summary = filing.compute(&table).unwrap();
+        assert_eq!((summary.deduction_label, summary.deduction, summary.taxable_income), ("Itemized", 25000.0, 25000.0));
+
+        let mut small = TaxReturn::new(FilingStatus::Single);
+        small.add_taxable("Salary", &Wage(5000.0));
+        let summary = small.compute(&table).unwrap();
+        assert_eq!((summary.taxable_income, summary.tax), (0.0, 0.0));
+        assert!(summary.breakdown.is_empty());
+        assert!(matches!(TaxReturn::new(FilingStatus::HeadOfHousehold).compute(&table), Err(TaxError::NoSchedule(_))));
+    }
+
+    #[test]
+    fn itemize_rejects_invalid_amounts() {
+        let mut filing = TaxReturn::new(FilingStatus::Single);
+        for amount in [-100.0, 0.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(filing.itemize("Bad", amount), Err(TaxError::InvalidDeduction { .. })));
+        }
+        assert!(filing.deductions.is_empty());
+    }
+}
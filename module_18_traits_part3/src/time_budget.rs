@@ -0,0 +1,136 @@
+// Time budgeting. Every block of time is logged against a week, a category (e.g. "Family") and an activity, and optionally
+// the person it was spent with. Totals come back as QualityTime, so weekly goals are reported through the same
+// goals::report code that money uses.
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use crate::QualityTime;
+use crate::clock::hours_minutes;
+use crate::goals::{self, Goal, GoalReport};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeEntry {
+    pub week: u32,
+    pub category: String,
+    pub activity: String,
+    pub person: Option<String>,
+    pub minutes: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeError {
+    ZeroMinutes,
+    TooLong(u32), // More minutes than a week has
+}
+
+impl Display for TimeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeError::ZeroMinutes => write!(f, "A time entry needs at least one minute"),
+            TimeError::TooLong(minutes) => write!(f, "{} doesn't fit in a week", hours_minutes(*minutes as f64)),
+        }
+    }
+}
+
+const MINUTES_PER_WEEK: u32 = 7 * 24 * 60;
+
+#[derive(Debug, Clone, Default)]
+pub struct TimeBudget {
+    entries: Vec<TimeEntry>,
+    weekly_goals: BTreeMap<String, u32>, // Category to target minutes per week
+}
+
+impl TimeBudget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Categories match case-insensitively like spent_on, so a second goal for "family" replaces the one for "Family" and
+    // keeps the first spelling
+    pub fn set_goal(&mut self, category: &str, minutes_per_week: u32) {
+        match self.weekly_goals.iter_mut().find(|(existing, _)| existing.eq_ignore_ascii_case(category)) {
+            Some((_, goal)) => *goal = minutes_per_week,
+            None => {
+                self.weekly_goals.insert(category.to_string(), minutes_per_week);
+            }
+        }
+    }
+
+    pub fn log(&mut self, week: u32, category: &str, activity: &str, person: Option<&str>, minutes: u32) -> Result<(), TimeError> {
+        if minutes == 0 {
+            return Err(TimeError::ZeroMinutes);
+        }
+        if minutes > MINUTES_PER_WEEK {
+            return Err(TimeError::TooLong(minutes));
+        }
+        self.entries.push(TimeEntry {
+            week,
+            category: category.to_string(),
+            activity: activity.to_string(),
+            person: person.map(str::to_string),
+            minutes,
+        });
+        Ok(())
+    }
+
+    fn total<F: Fn(&TimeEntry) -> bool>(&self, keep: F) -> QualityTime {
+        QualityTime { amount: self.entries.iter().filter(|entry| keep(entry)).map(|entry| entry.minutes).sum() }
+    }
+
+    pub fn spent_on(&self, week: u32, category: &str) -> QualityTime {
+        self.total(|entry| entry.week == week && entry.category.eq_ignore_ascii_case(category))
+    }
+
+    pub fn spent_with(&self, week: u32, person: &str) -> QualityTime {
+        self.total(|entry| entry.week == week && entry.person.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(person)))
+    }
+
+    // Everyone time was spent with that week, most time first. Names match case-insensitively like spent_with, and each
+    // person is listed under the first spelling that was logged.
+    pub fn people(&self, week: u32) -> Vec<(String, QualityTime)> {
+        let mut names: Vec<&str> = self.entries.iter().filter(|entry| entry.week == week).filter_map(|entry| entry.person.as_deref()).collect();
+        names.sort_by_key(|name| name.to_ascii_lowercase()); // Stable, so the first spelling logged stays first
+        names.dedup_by(|later, earlier| later.eq_ignore_ascii_case(earlier));
+        let mut people: Vec<(String, QualityTime)> = names.into_iter().map(|name| (name.to_string(), self.spent_with(week, name))).collect();
+        people.sort_by_key(|(_, time)| Reverse(time.amount));
+        people
+    }
+
+    pub fn weekly_report(&self, week: u32) -> GoalReport {
+        let spent: Vec<(&String, QualityTime)> = self.weekly_goals.keys().map(|category| (category, self.spent_on(week, category))).collect();
+        let goals: Vec<Goal<'_, u32>> = spent
+            .iter()
+            .map(|(category, time)| Goal::new(category, time, self.weekly_goals[*category]))
+            .collect();
+        goals::report(&format!("Week {} time budget", week), &goals, hours_minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn people_are_grouped_regardless_of_case() {
+        let mut budget = TimeBudget::new();
+        budget.log(1, "Family", "Dinner", Some("Sam"), 90).unwrap();
+        budget.log(1, "Family", "Board games", Some("sam"), 60).unwrap();
+        budget.log(1, "Friends", "Coffee", Some("Alex"), 30).unwrap();
+        let people: Vec<(String, u32)> = budget.people(1).into_iter().map(|(name, time)| (name, time.amount)).collect();
+        assert_eq!(people, vec![(String::from("Sam"), 150), (String::from("Alex"), 30)]);
+    }
+
+    #[test]
+    fn goals_are_keyed_regardless_of_case() {
+        let mut budget = TimeBudget::new();
+        budget.set_goal("Family", 300);
+        budget.set_goal("FAMILY", 420);
+        budget.set_goal("Friends", 120);
+        let goals: Vec<(&str, u32)> = budget.weekly_goals.iter().map(|(category, minutes)| (category.as_str(), *minutes)).collect();
+        assert_eq!(goals, vec![("Family", 420), ("Friends", 120)]);
+
+        budget.log(1, "family", "Dinner", None, 90).unwrap();
+        assert_eq!(budget.spent_on(1, "Family").amount, 90);
+    }
+}
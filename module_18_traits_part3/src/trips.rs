@@ -0,0 +1,348 @@
+// Route planning across flights and bus trips. A Timetable turns each Flight or BusTrip into a Leg with a parsed departure
+// time, a duration and a price, and indexes the legs by origin city to form the route graph. The planner is a label-setting
+// search: partial itineraries come off a queue best first for the traveller's preference, each one only continues on legs
+// that respect the minimum connection times and never revisit a city, and one that is no better than another already
+// reaching the same city on the same mode is dropped. At the destination the mode no longer matters, so there every
+// itinerary competes with every other. Timetables are a single day of departures, a trip may arrive after midnight.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{Display, Formatter};
+
+use crate::clock::{hours_minutes, Clock, ClockError};
+use crate::money::{Currency, Money};
+use crate::{BusTrip, Flight};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Flight,
+    Bus,
+}
+
+// Minimum minutes between arriving on one leg and departing on the next, by the modes of the two legs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConnectionTimes {
+    pub flight_to_flight: u32,
+    pub flight_to_bus: u32,
+    pub bus_to_flight: u32, // Time to get through security
+    pub bus_to_bus: u32,
+}
+
+impl Default for ConnectionTimes {
+    fn default() -> Self {
+        Self { flight_to_flight: 45, flight_to_bus: 30, bus_to_flight: 90, bus_to_bus: 10 }
+    }
+}
+
+impl ConnectionTimes {
+    pub fn between(&self, arriving: Mode, departing: Mode) -> u32 {
+        match (arriving, departing) {
+            (Mode::Flight, Mode::Flight) => self.flight_to_flight,
+            (Mode::Flight, Mode::Bus) => self.flight_to_bus,
+            (Mode::Bus, Mode::Flight) => self.bus_to_flight,
+            (Mode::Bus, Mode::Bus) => self.bus_to_bus,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leg {
+    pub mode: Mode,
+    pub origin: String,
+    pub destination: String,
+    pub departs: Clock,
+    pub arrives: Clock,
+    pub price: Money,
+}
+
+impl Display for Leg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} {} {} --> {} {} ({})", self.mode, self.origin, self.departs, self.destination, self.arrives, self.price)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TripError {
+    Time(ClockError),
+    NoRoute { from: String, to: String },
+    TooLong { origin: String, destination: String, minutes: u32 }, // The arrival time doesn't fit in a Clock
+}
+
+impl Display for TripError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TripError::Time(error) => write!(f, "{}", error),
+            TripError::NoRoute { from, to } => write!(f, "No way to get from {} to {}", from, to),
+            TripError::TooLong { origin, destination, minutes } => {
+                write!(f, "A {} minute trip from {} to {} arrives too late to keep track of", minutes, origin, destination)
+            }
+        }
+    }
+}
+
+impl From<ClockError> for TripError {
+    fn from(error: ClockError) -> Self {
+        TripError::Time(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Preference {
+    Fastest,         // Earliest arrival, then least time on the road
+    Cheapest,
+    FewestTransfers,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Itinerary {
+    pub legs: Vec<Leg>,
+}
+
+impl Itinerary {
+    pub fn departs(&self) -> Clock {
+        self.legs[0].departs
+    }
+
+    pub fn arrives(&self) -> Clock {
+        self.legs[self.legs.len() - 1].arrives
+    }
+
+    pub fn total_minutes(&self) -> u32 {
+        self.departs().minutes_until(self.arrives()).unwrap_or(0)
+    }
+
+    pub fn price(&self) -> Money {
+        self.legs.iter().fold(Money::zero(self.legs[0].price.currency()), |total, leg| total + leg.price)
+    }
+
+    pub fn transfers(&self) -> usize {
+        self.legs.len() - 1
+    }
+
+    // Smaller is better for the given preference, the other measures break ties
+    fn key(&self, preference: Preference) -> (i64, i64, i64, i64) {
+        let (arrives, minutes, cents, transfers) =
+            (self.arrives().minutes() as i64, self.total_minutes() as i64, self.price().minor(), self.transfers() as i64);
+        match preference {
+            Preference::Fastest => (arrives, minutes, cents, transfers),
+            Preference::Cheapest => (cents, arrives, minutes, transfers),
+            Preference::FewestTransfers => (transfers, arrives, minutes, cents),
+        }
+    }
+
+    // At least as good on every measure. Leaving later means less time door to door for the same arrival.
+    fn dominates(&self, other: &Itinerary) -> bool {
+        self.arrives() <= other.arrives()
+            && self.departs() >= other.departs()
+            && self.price().minor() <= other.price().minor()
+            && self.transfers() <= other.transfers()
+    }
+}
+
+impl Display for Itinerary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} --> {}, {} door to door, {} transfer(s), {}",
+            self.departs(),
+            self.arrives(),
+            hours_minutes(self.total_minutes() as f64),
+            self.transfers(),
+            self.price()
+        )?;
+        for (i, leg) in self.legs.iter().enumerate() {
+            if i > 0 {
+                let layover = self.legs[i - 1].arrives.minutes_until(leg.departs).unwrap_or(0);
+                writeln!(f, "    {} layover in {}", hours_minutes(layover as f64), leg.origin)?;
+            }
+            writeln!(f, "  {}", leg)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Timetable {
+    legs: Vec<Leg>,
+    departures: HashMap<String, Vec<usize>>, // The route graph: city to the legs leaving it
+    connections: ConnectionTimes,
+}
+
+impl Timetable {
+    pub fn new(connections: ConnectionTimes) -> Self {
+        Self { legs: Vec::new(), departures: HashMap::new(), connections }
+    }
+
+    fn add_leg(&mut self, mode: Mode, origin: &str, destination: &str, time: &str, minutes: u32, price: Money) -> Result<(), TripError> {
+        let departs = Clock::parse(time)?;
+        let arrives = departs.checked_add_minutes(minutes).ok_or_else(|| TripError::TooLong {
+            origin: origin.to_string(),
+            destination: destination.to_string(),
+            minutes,
+        })?;
+        self.departures.entry(origin.to_string()).or_default().push(self.legs.len());
+        self.legs.push(Leg {
+            mode,
+            origin: origin.to_string(),
+            destination: destination.to_string(),
+            departs,
+            arrives,
+            price,
+        });
+        Ok(())
+    }
+
+    pub fn add_flight(&mut self, flight: &Flight, minutes: u32, dollars: i64) -> Result<(), TripError> {
+        self.add_leg(Mode::Flight, &flight.origin, &flight.destination, &flight.time, minutes, Money::from_major(dollars, Currency::Usd))
+    }
+
+    pub fn add_bus(&mut self, trip: &BusTrip, minutes: u32, dollars: i64) -> Result<(), TripError> {
+        self.add_leg(Mode::Bus, &trip.origin, &trip.destination, &trip.time, minutes, Money::from_major(dollars, Currency::Usd))
+    }
+
+    // The itineraries worth considering from the city leaving no earlier than the given time: each one is faster, cheaper
+    // or has fewer transfers than every other
+    pub fn itineraries(&self, from: &str, to: &str, earliest: Clock) -> Vec<Itinerary> {
+        self.search(from, to, earliest, Preference::Fastest, false)
+    }
+
+    pub fn plan(&self, from: &str, to: &str, earliest: Clock, preference: Preference) -> Result<Itinerary, TripError> {
+        self.search(from, to, earliest, preference, true)
+            .pop()
+            .ok_or_else(|| TripError::NoRoute { from: from.to_string(), to: to.to_string() })
+    }
+
+    // Every measure in the key only grows as legs are added, so the first itinerary to reach the destination is the best
+    // one and the search can stop there. Without stopping it goes on to find the rest of the ones nothing beats.
+    fn search(&self, from: &str, to: &str, earliest: Clock, preference: Preference, first_only: bool) -> Vec<Itinerary> {
+        let mut partial: Vec<Itinerary> = Vec::new();
+        let mut queue = BinaryHeap::new();
+        let push = |itinerary: Itinerary, partial: &mut Vec<Itinerary>, queue: &mut BinaryHeap<_>| {
+            queue.push(Reverse((itinerary.key(preference), partial.len())));
+            partial.push(itinerary);
+        };
+        for &first in self.departures.get(from).into_iter().flatten() {
+            let leg = &self.legs[first];
+            if leg.departs >= earliest && leg.destination != from {
+                push(Itinerary { legs: vec![leg.clone()] }, &mut partial, &mut queue);
+            }
+        }
+
+        // Itineraries taken off the queue, by where they end and on what mode. The mode is None at the destination.
+        let mut kept: HashMap<(String, Option<Mode>), Vec<usize>> = HashMap::new();
+        let mut found = Vec::new();
+        while let Some(Reverse((_, index))) = queue.pop() {
+            let last = partial[index].legs[partial[index].legs.len() - 1].clone();
+            let mode = if last.destination == to { None } else { Some(last.mode) };
+            let reached = kept.entry((last.destination.clone(), mode)).or_default();
+            if reached.iter().any(|&other| partial[other].dominates(&partial[index])) {
+                continue;
+            }
+            reached.push(index);
+            if last.destination == to {
+                found.push(partial[index].clone());
+                if first_only {
+                    break;
+                }
+                continue;
+            }
+            for &next in self.departures.get(&last.destination).into_iter().flatten() {
+                let leg = &self.legs[next];
+                // Past the end of the clock nothing can be caught any more
+                let Some(ready) = last.arrives.checked_add_minutes(self.connections.between(last.mode, leg.mode)) else {
+                    continue;
+                };
+                let visited = leg.destination == from || partial[index].legs.iter().any(|taken| taken.destination == leg.destination);
+                if leg.departs < ready || visited {
+                    continue;
+                }
+                let mut longer = partial[index].clone();
+                longer.legs.push(leg.clone());
+                push(longer, &mut partial, &mut queue);
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bus(timetable: &mut Timetable, origin: &str, destination: &str, time: &str, minutes: u32, dollars: i64) {
+        timetable.add_bus(&BusTrip::new(origin, destination, time), minutes, dollars).unwrap();
+    }
+
+    #[test]
+    fn long_routes_are_found() {
+        let mut timetable = Timetable::new(ConnectionTimes::default());
+        let stops = ["A", "B", "C", "D", "E", "F", "G"];
+        for (hour, pair) in stops.windows(2).enumerate() {
+            bus(&mut timetable, pair[0], pair[1], &format!("{}:00", 6 + hour * 2), 60, 10);
+        }
+        let itinerary = timetable.plan("A", "G", Clock::hm(5, 0), Preference::Fastest).unwrap();
+        assert_eq!(itinerary.legs.len(), 6);
+        assert_eq!(itinerary.arrives(), Clock::hm(17, 0));
+    }
+
+    #[test]
+    fn each_preference_gets_its_best_route() {
+        let mut timetable = Timetable::new(ConnectionTimes::default());
+        bus(&mut timetable, "A", "D", "8:00", 300, 90); // Direct
+        bus(&mut timetable, "A", "B", "6:00", 60, 10);
+        bus(&mut timetable, "B", "D", "7:30", 120, 60); // Arrives first
+        bus(&mut timetable, "B", "C", "7:15", 60, 5);
+        bus(&mut timetable, "C", "D", "9:00", 180, 5); // Cheapest
+        let plan = |preference| timetable.plan("A", "D", Clock::hm(0, 0), preference).unwrap();
+        assert_eq!(plan(Preference::Fastest).arrives(), Clock::hm(9, 30));
+        assert_eq!(plan(Preference::Cheapest).price(), Money::from_major(20, Currency::Usd));
+        assert_eq!(plan(Preference::FewestTransfers).transfers(), 0);
+        assert_eq!(timetable.itineraries("A", "D", Clock::hm(0, 0)).len(), 3);
+    }
+
+    #[test]
+    fn connections_need_their_minimum_time() {
+        let mut timetable = Timetable::new(ConnectionTimes::default());
+        bus(&mut timetable, "A", "B", "6:00", 60, 10);
+        timetable.add_flight(&Flight::new("B", "C", "8:00"), 60, 100).unwrap(); // 60 minutes after the bus, security needs 90
+        assert_eq!(
+            timetable.plan("A", "C", Clock::hm(0, 0), Preference::Fastest),
+            Err(TripError::NoRoute { from: "A".to_string(), to: "C".to_string() })
+        );
+        timetable.add_flight(&Flight::new("B", "C", "8:30"), 60, 100).unwrap();
+        assert_eq!(timetable.plan("A", "C", Clock::hm(0, 0), Preference::Fastest).unwrap().arrives(), Clock::hm(9, 30));
+    }
+
+    #[test]
+    fn arrival_modes_compete_at_the_destination() {
+        let mut timetable = Timetable::new(ConnectionTimes::default());
+        bus(&mut timetable, "A", "B", "8:00", 60, 20);
+        timetable.add_flight(&Flight::new("A", "B", "8:00"), 90, 150).unwrap(); // Later and dearer than the bus
+        let itineraries = timetable.itineraries("A", "B", Clock::hm(0, 0));
+        assert_eq!(itineraries.len(), 1);
+        assert_eq!(itineraries[0].legs[0].mode, Mode::Bus);
+
+        // Along the way a slower flight is still kept, since only it makes the onward flight connection
+        bus(&mut timetable, "B", "C", "9:15", 180, 10);
+        timetable.add_flight(&Flight::new("B", "C", "10:15"), 60, 100).unwrap();
+        let modes: Vec<Vec<Mode>> = timetable
+            .itineraries("A", "C", Clock::hm(0, 0))
+            .iter()
+            .map(|itinerary| itinerary.legs.iter().map(|leg| leg.mode).collect())
+            .collect();
+        assert_eq!(modes, vec![vec![Mode::Flight, Mode::Flight], vec![Mode::Bus, Mode::Bus]]);
+    }
+
+    #[test]
+    fn overlong_legs_are_rejected() {
+        let mut timetable = Timetable::new(ConnectionTimes::default());
+        let error = timetable.add_bus(&BusTrip::new("A", "B", "23:00"), u32::MAX, 10).unwrap_err();
+        assert!(matches!(error, TripError::TooLong { minutes: u32::MAX, .. }));
+        assert!(timetable.plan("A", "B", Clock::hm(0, 0), Preference::Fastest).is_err());
+
+        // A leg that arrives right at the end of the clock can't connect to anything
+        bus(&mut timetable, "A", "B", "0:00", u32::MAX, 10);
+        bus(&mut timetable, "B", "C", "1:00", 60, 10);
+        assert!(timetable.itineraries("A", "C", Clock::hm(0, 0)).is_empty());
+        assert_eq!(timetable.itineraries("A", "B", Clock::hm(0, 0)).len(), 1);
+    }
+}
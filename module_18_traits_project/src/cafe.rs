@@ -0,0 +1,315 @@
+// Café ordering built on top of Drinkable. The menu holds any Drinkable as a trait object, so a new drink type only needs
+// a Drinkable impl to be sold here. Customizations are priced separately from the drink itself through the Pricing table.
+use std::fmt::{Display, Formatter, Result};
+
+use crate::inventory::{self, Ingredient, Inventory, OutOfStock, Recipe};
+use crate::money::Money;
+use crate::sku;
+use crate::{Drinkable, Milk};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Size {
+    Small,
+    Medium,
+    Large,
+}
+
+impl Size {
+    // Drinks are listed on the menu at their small size, bigger sizes scale the calories and ingredients up
+    pub fn calorie_multiplier(&self) -> f64 {
+        match self {
+            Size::Small => 1.0,
+            Size::Medium => 1.25,
+            Size::Large => 1.5,
+        }
+    }
+}
+
+impl Display for Size {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Size::Small => write!(f, "Small"),
+            Size::Medium => write!(f, "Medium"),
+            Size::Large => write!(f, "Large"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Customization {
+    pub size: Size,
+    pub milk: Option<Milk>, // Swaps the drink's milk, only for espresso based drinks
+    pub extra_shots: u32,   // Only for espresso based drinks
+    pub flavor: Option<String>, // A syrup pump, e.g. "Vanilla"
+}
+
+impl Customization {
+    pub fn new(size: Size) -> Self {
+        Self { size, milk: None, extra_shots: 0, flavor: None }
+    }
+
+    pub fn with_milk(mut self, milk: Milk) -> Self {
+        self.milk = Some(milk);
+        self
+    }
+
+    pub fn with_extra_shots(mut self, shots: u32) -> Self {
+        self.extra_shots = shots;
+        self
+    }
+
+    pub fn with_flavor(mut self, flavor: &str) -> Self {
+        self.flavor = Some(flavor.to_string());
+        self
+    }
+}
+
+impl Display for Customization {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.size)?;
+        if let Some(milk) = &self.milk {
+            write!(f, ", {:?} milk", milk)?;
+        }
+        if self.extra_shots > 0 {
+            write!(f, ", +{} shot(s)", self.extra_shots)?;
+        }
+        if let Some(flavor) = &self.flavor {
+            write!(f, ", {} syrup", flavor)?;
+        }
+        Ok(())
+    }
+}
+
+// The café's price list for everything that isn't the drink itself
+#[derive(Debug, Clone)]
+pub struct Pricing {
+    pub medium_surcharge: Money,
+    pub large_surcharge: Money,
+    pub whole_milk: Money,
+    pub oat_milk: Money,
+    pub almond_milk: Money,
+    pub extra_shot: Money,
+    pub flavor: Money,
+    pub extra_shot_calories: u32,
+    pub flavor_calories: u32,
+}
+
+impl Default for Pricing {
+    fn default() -> Self {
+        Self {
+            medium_surcharge: Money::from_cents(50),
+            large_surcharge: Money::from_cents(100),
+            whole_milk: Money::ZERO,
+            oat_milk: Money::from_cents(75),
+            almond_milk: Money::from_cents(60),
+            extra_shot: Money::from_cents(90),
+            flavor: Money::from_cents(50),
+            extra_shot_calories: 5,
+            flavor_calories: 20,
+        }
+    }
+}
+
+impl Pricing {
+    pub fn size_surcharge(&self, size: Size) -> Money {
+        match size {
+            Size::Small => Money::ZERO,
+            Size::Medium => self.medium_surcharge,
+            Size::Large => self.large_surcharge,
+        }
+    }
+
+    pub fn milk_surcharge(&self, milk: &Milk) -> Money {
+        match milk {
+            Milk::Whole => self.whole_milk,
+            Milk::Oat => self.oat_milk,
+            Milk::Almond => self.almond_milk,
+        }
+    }
+
+    pub fn customization_price(&self, customization: &Customization) -> Money {
+        let milk = customization.milk.as_ref().map(|milk| self.milk_surcharge(milk)).unwrap_or(Money::ZERO);
+        let flavor = if customization.flavor.is_some() { self.flavor } else { Money::ZERO };
+        self.size_surcharge(customization.size) + milk + self.extra_shot * customization.extra_shots + flavor
+    }
+
+    pub fn customization_calories(&self, customization: &Customization) -> u32 {
+        let flavor = if customization.flavor.is_some() { self.flavor_calories } else { 0 };
+        self.extra_shot_calories * customization.extra_shots + flavor
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderError {
+    NotOnMenu(String),
+    NotCustomizable { drink: String, option: &'static str },
+    ZeroQuantity,
+}
+
+impl Display for OrderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            OrderError::NotOnMenu(name) => write!(f, "{} is not on the menu", name),
+            OrderError::NotCustomizable { drink, option } => write!(f, "{} can't be made with {}", drink, option),
+            OrderError::ZeroQuantity => write!(f, "Quantity must be at least 1"),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Menu {
+    drinks: Vec<Box<dyn Drinkable>>,
+    pricing: Pricing,
+}
+
+impl Menu {
+    pub fn new(pricing: Pricing) -> Self {
+        Self { drinks: Vec::new(), pricing }
+    }
+
+    pub fn add<D: Drinkable + 'static>(&mut self, drink: D) {
+        self.drinks.push(Box::new(drink));
+    }
+
+    pub fn find(&self, name: &str) -> Option<&dyn Drinkable> {
+        self.drinks.iter().find(|drink| drink.name().eq_ignore_ascii_case(name)).map(|drink| drink.as_ref())
+    }
+
+    pub fn pricing(&self) -> &Pricing {
+        &self.pricing
+    }
+
+    // Alphabetical, using the Ord implementation for dyn Drinkable
+    pub fn sort_by_name(&mut self) {
+        self.drinks.sort();
+    }
+
+    // Cheapest first. The sort is stable, so drinks with the same price keep their current order.
+    pub fn sort_by_price(&mut self) {
+        self.drinks.sort_by(|a, b| sku::by_price(a.as_ref(), b.as_ref()));
+    }
+
+    pub fn order(&self) -> Order<'_> {
+        Order { menu: self, lines: Vec::new() }
+    }
+}
+
+impl Display for Menu {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(f, "** Menu **")?;
+        for drink in &self.drinks {
+            writeln!(f, "{:<24} {:>7} {:>5} cal", drink.name(), drink.price(), drink.calories())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TicketLine {
+    pub drink: String,
+    pub customization: Customization,
+    pub quantity: u32,
+    pub unit_price: Money,
+    pub unit_calories: u32,
+    pub recipe: Recipe, // Ingredients for one drink with its customization applied
+}
+
+pub struct Order<'a> {
+    menu: &'a Menu,
+    lines: Vec<TicketLine>,
+}
+
+impl Order<'_> {
+    pub fn add(&mut self, name: &str, customization: Customization, quantity: u32) -> std::result::Result<&TicketLine, OrderError> {
+        let drink = self.menu.find(name).ok_or_else(|| OrderError::NotOnMenu(name.to_string()))?;
+        if quantity == 0 {
+            return Err(OrderError::ZeroQuantity);
+        }
+        if !drink.is_espresso_based() {
+            if customization.milk.is_some() {
+                return Err(OrderError::NotCustomizable { drink: drink.name(), option: "milk" });
+            }
+            if customization.extra_shots > 0 {
+                return Err(OrderError::NotCustomizable { drink: drink.name(), option: "extra shots" });
+            }
+        }
+
+        let pricing = self.menu.pricing();
+        let drink_calories = match customization.milk {
+            Some(milk) => drink.nutrition_with_milk(milk).calories.round() as u32,
+            None => drink.calories(),
+        };
+        let base_calories = (drink_calories as f64 * customization.size.calorie_multiplier()).round() as u32;
+        self.lines.push(TicketLine {
+            drink: drink.name(),
+            unit_price: drink.price() + pricing.customization_price(&customization),
+            unit_calories: base_calories + pricing.customization_calories(&customization),
+            recipe: customized_recipe(drink, &customization),
+            customization,
+            quantity,
+        });
+        Ok(&self.lines[self.lines.len() - 1])
+    }
+
+    pub fn ticket(&self) -> Ticket {
+        Ticket { lines: self.lines.clone() }
+    }
+
+    // Makes every drink on the order from stock. If anything is short, no drink is made and nothing is drawn.
+    pub fn fulfil(&self, stock: &mut Inventory) -> std::result::Result<Ticket, OutOfStock> {
+        let recipes: Vec<Recipe> = self.lines.iter().map(|line| inventory::scale(&line.recipe, line.quantity as f64)).collect();
+        stock.draw(&inventory::combine(&recipes))?;
+        Ok(self.ticket())
+    }
+}
+
+fn customized_recipe(drink: &dyn Drinkable, customization: &Customization) -> Recipe {
+    let mut recipe: Recipe = inventory::scale(&drink.recipe(), customization.size.calorie_multiplier())
+        .into_iter()
+        .map(|(ingredient, amount)| match (ingredient, customization.milk) {
+            (Ingredient::Milk(_), Some(milk)) => (Ingredient::Milk(milk), amount),
+            (ingredient, _) => (ingredient, amount),
+        })
+        .collect();
+    if customization.extra_shots > 0 {
+        recipe.push((Ingredient::Beans, inventory::BEANS_GRAMS_PER_SHOT * customization.extra_shots as f64));
+    }
+    if let Some(flavor) = &customization.flavor {
+        recipe.push((Ingredient::Syrup(flavor.clone()), inventory::SYRUP_PUMPS_PER_FLAVOR));
+    }
+    inventory::combine(&[recipe])
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticket {
+    pub lines: Vec<TicketLine>,
+}
+
+impl Ticket {
+    pub fn total_price(&self) -> Money {
+        self.lines.iter().map(|line| line.unit_price * line.quantity).sum()
+    }
+
+    pub fn total_calories(&self) -> u32 {
+        self.lines.iter().map(|line| line.unit_calories * line.quantity).sum()
+    }
+}
+
+impl Display for Ticket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(f, "** Order Ticket **")?;
+        for line in &self.lines {
+            writeln!(
+                f,
+                "{} x {} ({}) @ {} --> {}, {} cal",
+                line.quantity,
+                line.drink,
+                line.customization,
+                line.unit_price,
+                line.unit_price * line.quantity,
+                line.unit_calories * line.quantity
+            )?;
+        }
+        write!(f, "Total: {}, {} calories", self.total_price(), self.total_calories())
+    }
+}
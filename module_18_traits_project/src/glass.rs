@@ -0,0 +1,152 @@
+// The liquid side of a drink: how much it holds, how much is left, and every sip and refill along the way.
+// Each Drinkable owns a Glass so sipping and refilling work the same for every drink type.
+use std::fmt::{Display, Formatter, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Consumption {
+    Sip(f64),    // Ounces drunk
+    Refill(f64), // Ounces poured back in
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DrinkError {
+    InvalidAmount(f64), // Zero, negative or not a number
+    OverConsumption { requested: f64, remaining: f64 },
+    Overfill { requested: f64, space: f64 },
+}
+
+impl Display for DrinkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            DrinkError::InvalidAmount(ounces) => write!(f, "{} ounces is not a valid amount", ounces),
+            DrinkError::OverConsumption { requested, remaining } => {
+                write!(f, "Can't drink {:.1} ounces, only {:.1} left", requested, remaining)
+            }
+            DrinkError::Overfill { requested, space } => {
+                write!(f, "Can't pour {:.1} ounces, only {:.1} ounces of space", requested, space)
+            }
+        }
+    }
+}
+
+// Volumes are kept in whole hundredths of an ounce, so sipping 0.3 oz forty times empties a 12 oz glass exactly instead
+// of leaving 0.2999999999999941 oz that the last sip can't take
+const UNITS_PER_OUNCE: f64 = 100.0;
+
+fn to_units(ounces: f64) -> u64 {
+    (ounces * UNITS_PER_OUNCE).round() as u64
+}
+
+fn to_ounces(units: u64) -> f64 {
+    units as f64 / UNITS_PER_OUNCE
+}
+
+// Anything that rounds to less than a hundredth of an ounce is too small to measure
+fn check_amount(ounces: f64) -> std::result::Result<u64, DrinkError> {
+    if !ounces.is_finite() || ounces <= 0.0 || to_units(ounces) == 0 {
+        return Err(DrinkError::InvalidAmount(ounces));
+    }
+    Ok(to_units(ounces))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glass {
+    capacity_hundredths: u64,
+    remaining_hundredths: u64,
+    history: Vec<Consumption>, // In ounces, already rounded to the hundredth that was actually poured or drunk
+}
+
+impl Glass {
+    // Starts full
+    pub fn new(capacity: f64) -> Self {
+        let capacity = if capacity.is_finite() && capacity > 0.0 { to_units(capacity) } else { 0 };
+        Self { capacity_hundredths: capacity, remaining_hundredths: capacity, history: Vec::new() }
+    }
+
+    pub fn capacity(&self) -> f64 {
+        to_ounces(self.capacity_hundredths)
+    }
+
+    pub fn remaining(&self) -> f64 {
+        to_ounces(self.remaining_hundredths)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining_hundredths == 0
+    }
+
+    pub fn history(&self) -> &[Consumption] {
+        &self.history
+    }
+
+    pub fn sip(&mut self, ounces: f64) -> std::result::Result<(), DrinkError> {
+        let units = check_amount(ounces)?;
+        if units > self.remaining_hundredths {
+            return Err(DrinkError::OverConsumption { requested: ounces, remaining: self.remaining() });
+        }
+        self.remaining_hundredths -= units;
+        self.history.push(Consumption::Sip(to_ounces(units)));
+        Ok(())
+    }
+
+    pub fn refill(&mut self, ounces: f64) -> std::result::Result<(), DrinkError> {
+        let units = check_amount(ounces)?;
+        let space = self.capacity_hundredths - self.remaining_hundredths;
+        if units > space {
+            return Err(DrinkError::Overfill { requested: ounces, space: to_ounces(space) });
+        }
+        self.remaining_hundredths += units;
+        self.history.push(Consumption::Refill(to_ounces(units)));
+        Ok(())
+    }
+
+    // Total ounces drunk across every sip. Refills don't count until they're sipped.
+    pub fn ounces_consumed(&self) -> f64 {
+        let units: u64 = self
+            .history
+            .iter()
+            .map(|event| match event {
+                Consumption::Sip(ounces) => to_units(*ounces),
+                Consumption::Refill(_) => 0,
+            })
+            .sum();
+        to_ounces(units)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_sips_empty_the_glass_exactly() {
+        for (capacity, sip, sips) in [(12.0, 0.3, 40), (8.0, 0.2, 40), (16.0, 0.4, 40)] {
+            let mut glass = Glass::new(capacity);
+            for _ in 0..sips {
+                glass.sip(sip).unwrap();
+            }
+            assert!(glass.is_empty());
+            assert_eq!(glass.ounces_consumed(), capacity);
+            assert!(matches!(glass.sip(sip), Err(DrinkError::OverConsumption { .. })));
+        }
+    }
+
+    #[test]
+    fn refills_fill_back_to_capacity_but_not_past_it() {
+        let mut glass = Glass::new(12.0);
+        glass.sip(0.1).unwrap();
+        glass.sip(0.2).unwrap();
+        glass.refill(0.3).unwrap();
+        assert_eq!(glass.remaining(), 12.0);
+        assert!(matches!(glass.refill(0.01), Err(DrinkError::Overfill { .. })));
+        assert_eq!(glass.ounces_consumed(), 0.3);
+    }
+
+    #[test]
+    fn amounts_too_small_to_measure_are_rejected() {
+        let mut glass = Glass::new(12.0);
+        for ounces in [0.0, -1.0, 0.001, f64::NAN] {
+            assert!(matches!(glass.sip(ounces), Err(DrinkError::InvalidAmount(_))));
+        }
+    }
+}
@@ -0,0 +1,225 @@
+// The café's ingredient supply. Every Drinkable lists its recipe, and preparing a drink draws that recipe from stock.
+// A drink is only made when every ingredient is available, so a failed order never leaves the stock half used. Stock is
+// counted in whole hundredths of a unit, like Glass volumes, so three draws of 0.1oz empty 0.3oz exactly.
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{Drinkable, Milk};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Ingredient {
+    Beans,                // Grams of ground coffee
+    Milk(Milk),           // Ounces
+    Syrup(String),        // Pumps of a flavor syrup
+    SodaCanister(String), // Ounces of a soda flavor from the fountain canister
+}
+
+impl Ingredient {
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Ingredient::Beans => "g",
+            Ingredient::Milk(_) => "oz",
+            Ingredient::Syrup(_) => "pumps",
+            Ingredient::SodaCanister(_) => "oz",
+        }
+    }
+}
+
+impl Display for Ingredient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Ingredient::Beans => write!(f, "Coffee beans"),
+            Ingredient::Milk(milk) => write!(f, "{:?} milk", milk),
+            Ingredient::Syrup(flavor) => write!(f, "{} syrup", flavor),
+            Ingredient::SodaCanister(flavor) => write!(f, "{} soda canister", flavor),
+        }
+    }
+}
+
+pub type Recipe = Vec<(Ingredient, f64)>;
+
+pub const BEANS_GRAMS_PER_OUNCE: f64 = 2.0; // Ground coffee for each ounce of brewed coffee
+pub const BEANS_GRAMS_PER_SHOT: f64 = 9.0;
+pub const SYRUP_PUMPS_PER_FLAVOR: f64 = 1.0;
+
+pub fn scale(recipe: &[(Ingredient, f64)], factor: f64) -> Recipe {
+    recipe.iter().map(|(ingredient, amount)| (ingredient.clone(), amount * factor)).collect()
+}
+
+// Merges repeated ingredients so each one appears once with its total amount
+pub fn combine(recipes: &[Recipe]) -> Recipe {
+    let mut totals: Vec<(Ingredient, f64)> = Vec::new();
+    for (ingredient, amount) in recipes.iter().flatten() {
+        match totals.iter_mut().find(|(existing, _)| existing == ingredient) {
+            Some((_, total)) => *total += amount,
+            None => totals.push((ingredient.clone(), *amount)),
+        }
+    }
+    totals
+}
+
+const UNITS_PER_AMOUNT: f64 = 100.0;
+
+fn to_units(amount: f64) -> u64 {
+    (amount * UNITS_PER_AMOUNT).round() as u64 // Negative and NaN amounts become 0
+}
+
+fn to_amount(units: u64) -> f64 {
+    units as f64 / UNITS_PER_AMOUNT
+}
+
+// What a recipe takes of each ingredient, rounded to hundredths once the repeats are added up
+fn needs(recipe: &[(Ingredient, f64)]) -> Vec<(Ingredient, u64)> {
+    combine(&[recipe.to_vec()]).into_iter().map(|(ingredient, amount)| (ingredient, to_units(amount))).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidAmount(pub f64); // Zero, negative, not a number or more than the stock can count
+
+impl Display for InvalidAmount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "Can't restock {} of an ingredient", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shortage {
+    pub ingredient: Ingredient,
+    pub needed: f64,
+    pub available: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutOfStock(pub Vec<Shortage>); // Every ingredient that came up short, not just the first
+
+impl Display for OutOfStock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "Out of stock:")?;
+        for shortage in &self.0 {
+            let unit = shortage.ingredient.unit();
+            write!(f, " {} (need {:.1}{}, have {:.1}{})", shortage.ingredient, shortage.needed, unit, shortage.available, unit)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LowStock {
+    pub ingredient: Ingredient,
+    pub level: f64,
+    pub threshold: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct Inventory {
+    stock: HashMap<Ingredient, u64>, // Hundredths of the ingredient's unit
+    thresholds: HashMap<Ingredient, u64>, // Reorder points for the low-stock report, also in hundredths
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Anything that rounds to less than a hundredth is too small to add
+    pub fn restock(&mut self, ingredient: Ingredient, amount: f64) -> std::result::Result<(), InvalidAmount> {
+        if !amount.is_finite() || amount <= 0.0 || to_units(amount) == 0 {
+            return Err(InvalidAmount(amount));
+        }
+        let level = self.stock.entry(ingredient).or_insert(0);
+        *level = level.checked_add(to_units(amount)).ok_or(InvalidAmount(amount))?;
+        Ok(())
+    }
+
+    pub fn set_low_threshold(&mut self, ingredient: Ingredient, threshold: f64) {
+        self.thresholds.insert(ingredient, to_units(threshold));
+    }
+
+    fn units(&self, ingredient: &Ingredient) -> u64 {
+        self.stock.get(ingredient).copied().unwrap_or(0)
+    }
+
+    pub fn level(&self, ingredient: &Ingredient) -> f64 {
+        to_amount(self.units(ingredient))
+    }
+
+    pub fn check(&self, recipe: &[(Ingredient, f64)]) -> std::result::Result<(), OutOfStock> {
+        let shortages: Vec<Shortage> = needs(recipe)
+            .into_iter()
+            .filter(|(ingredient, needed)| self.units(ingredient) < *needed)
+            .map(|(ingredient, needed)| Shortage { available: self.level(&ingredient), ingredient, needed: to_amount(needed) })
+            .collect();
+        if shortages.is_empty() { Ok(()) } else { Err(OutOfStock(shortages)) }
+    }
+
+    // Draws the whole recipe, or nothing at all if any ingredient is short
+    pub fn draw(&mut self, recipe: &[(Ingredient, f64)]) -> std::result::Result<(), OutOfStock> {
+        self.check(recipe)?;
+        for (ingredient, needed) in needs(recipe) {
+            if let Some(level) = self.stock.get_mut(&ingredient) {
+                *level -= needed;
+            }
+        }
+        Ok(())
+    }
+
+    // Makes the drink from stock and hands it back, e.g. inventory.prepare(Coffee::new("Latte", Milk::Oat, 12))
+    pub fn prepare<D: Drinkable>(&mut self, drink: D) -> std::result::Result<D, OutOfStock> {
+        self.draw(&drink.recipe())?;
+        Ok(drink)
+    }
+
+    // Everything at or below its reorder point, lowest first relative to its threshold
+    pub fn low_stock_report(&self) -> Vec<LowStock> {
+        let mut low: Vec<LowStock> = self
+            .thresholds
+            .iter()
+            .filter(|(ingredient, threshold)| self.units(ingredient) <= **threshold)
+            .map(|(ingredient, threshold)| LowStock { ingredient: ingredient.clone(), level: self.level(ingredient), threshold: to_amount(*threshold) })
+            .collect();
+        low.sort_by(|a, b| (a.level / a.threshold).total_cmp(&(b.level / b.threshold)).then_with(|| a.ingredient.cmp(&b.ingredient)));
+        low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syrup() -> Ingredient {
+        Ingredient::Syrup(String::from("Vanilla"))
+    }
+
+    #[test]
+    fn exact_draws_empty_the_stock() {
+        let mut stock = Inventory::new();
+        stock.restock(syrup(), 0.3).unwrap();
+        for _ in 0..3 {
+            stock.draw(&[(syrup(), 0.1)]).unwrap();
+        }
+        assert_eq!(stock.level(&syrup()), 0.0);
+        assert!(stock.draw(&[(syrup(), 0.1)]).is_err());
+    }
+
+    #[test]
+    fn bad_restocks_are_refused() {
+        let mut stock = Inventory::new();
+        stock.restock(Ingredient::Beans, 10.0).unwrap();
+        for amount in [-5.0, 0.0, 0.001, f64::NAN, f64::INFINITY] {
+            assert!(stock.restock(Ingredient::Beans, amount).is_err(), "{}", amount);
+        }
+        assert_eq!(stock.restock(Ingredient::Beans, 1e18), Err(InvalidAmount(1e18))); // The total no longer fits
+        assert_eq!(stock.level(&Ingredient::Beans), 10.0);
+    }
+
+    #[test]
+    fn short_recipes_take_nothing() {
+        let mut stock = Inventory::new();
+        stock.restock(Ingredient::Beans, 20.0).unwrap();
+        stock.restock(Ingredient::Milk(Milk::Oat), 4.0).unwrap();
+        let recipe = [(Ingredient::Beans, 18.0), (Ingredient::Milk(Milk::Oat), 3.0), (Ingredient::Milk(Milk::Oat), 3.0)];
+        let shortages = stock.draw(&recipe).unwrap_err().0;
+        assert_eq!(shortages, [Shortage { ingredient: Ingredient::Milk(Milk::Oat), needed: 6.0, available: 4.0 }]);
+        assert_eq!(stock.level(&Ingredient::Beans), 20.0);
+    }
+}
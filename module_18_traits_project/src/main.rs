@@ -1,15 +1,90 @@
+mod cafe;
+mod glass;
+mod inventory;
+mod money;
+mod nutrition;
+mod sku;
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::{Debug, Formatter, Display, Result};
+use std::hash::{Hash, Hasher};
+
+use cafe::{Customization, Menu, Pricing, Size};
+use glass::{DrinkError, Glass};
+use inventory::{Ingredient, Inventory, Recipe};
+use money::Money;
+use nutrition::{CaffeineTracker, Nutrition};
+use sku::Sku;
 
+const COFFEE_PRICE_PER_OUNCE: Money = Money::from_cents(30);
+const MILK_SHARE_OF_COFFEE: f64 = 0.25; // A quarter of every coffee is milk
+const SODA_CAN_OUNCES: f64 = 12.0;
 
 trait Drinkable {
-    fn consume(&mut self);
+    fn consume(&mut self) { // Finishes whatever is left
+        if !self.glass().is_empty() {
+            let rest = self.glass().remaining();
+            let _ = self.glass_mut().sip(rest);
+        }
+    }
     fn get_data(&self) -> String;
     fn stats(&self) {
         println!("{}", self.get_data());
     }
+    fn sku(&self) -> Sku; // The drink's identity, equality and hashing go by this alone
+    fn name(&self) -> String; // What the drink is called on the menu
+    fn price(&self) -> Money;
+    fn nutrition(&self) -> Nutrition; // For the whole drink when full
+    fn calories(&self) -> u32 {
+        self.nutrition().calories.round() as u32
+    }
+    fn is_espresso_based(&self) -> bool { // Only espresso based drinks can take a different milk or extra shots
+        false
+    }
+    fn nutrition_with_milk(&self, _milk: Milk) -> Nutrition { // The nutrition if the drink were made with a different milk
+        self.nutrition()
+    }
+    fn recipe(&self) -> Recipe; // What making one full drink takes out of the Inventory
+
+    // Every drink keeps its liquid in a Glass, the methods below are all built on it
+    fn glass(&self) -> &Glass;
+    fn glass_mut(&mut self) -> &mut Glass;
+    fn remaining(&self) -> f64 {
+        self.glass().remaining()
+    }
+    fn nutrition_per_ounce(&self) -> Nutrition { // nutrition() is for the full drink, so a sip only counts its share
+        let capacity = self.glass().capacity();
+        if capacity <= 0.0 {
+            return Nutrition::default();
+        }
+        self.nutrition() * (1.0 / capacity)
+    }
+    fn calories_per_ounce(&self) -> f64 {
+        self.nutrition_per_ounce().calories
+    }
+    fn sip(&mut self, ounces: f64) -> std::result::Result<f64, DrinkError> { // Returns the calories in the sip
+        self.glass_mut().sip(ounces)?;
+        Ok(ounces * self.calories_per_ounce())
+    }
+    fn refill(&mut self, ounces: f64) -> std::result::Result<(), DrinkError> {
+        self.glass_mut().refill(ounces)
+    }
+    fn top_up(&mut self) {
+        let space = self.glass().capacity() - self.glass().remaining();
+        if space > 0.0 {
+            let _ = self.glass_mut().refill(space);
+        }
+    }
+    fn nutrition_consumed(&self) -> Nutrition {
+        self.nutrition_per_ounce() * self.glass().ounces_consumed()
+    }
+    fn calories_consumed(&self) -> f64 {
+        self.nutrition_consumed().calories
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 enum Milk {
     Whole,
     Oat,
@@ -18,48 +93,90 @@ enum Milk {
 
 #[derive(Debug)]
 struct Soda {
+    sku: Sku,
     calories: u32,
-    price: f64,
+    price: Money,
     flavor: String,
-    percentage: u32
+    glass: Glass,
 }
 
 impl Soda {
-    fn new(calories: u32, price: f64, flavor: String) -> Self {
+    fn new(calories: u32, price: Money, flavor: String) -> Self {
         Self {
+            sku: Sku::from_parts(&["SODA", &flavor]),
             calories: calories,
             price: price,
             flavor: flavor,
-            percentage: 100
+            glass: Glass::new(SODA_CAN_OUNCES),
         }
     }
 }
 
+// Two sodas are the same drink when they're the same product (SKU). Use sku::by_price to compare what they cost.
 impl PartialEq for Soda {
     fn eq(&self, other: &Self) -> bool {
-        self.price == other.price
+        self.sku == other.sku
     }
 }
 
 impl Eq for Soda {}
 
+impl Hash for Soda {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sku.hash(state);
+    }
+}
+
+impl PartialOrd for Soda {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Soda {
+    fn cmp(&self, other: &Self) -> Ordering {
+        sku::by_name(self, other)
+    }
+}
+
 impl Clone for Soda {
     fn clone(&self) -> Self {
         Self {
+            sku: self.sku.clone(),
             calories: self.calories,
             price: self.price,
             flavor: self.flavor.clone(),
-            percentage: self.percentage
+            glass: self.glass.clone(),
         }
     }
 }
 
 impl Drinkable for Soda {
-    fn consume(&mut self) {
-        self.percentage = 0;
-    }
     fn get_data(&self) -> String {
-        format!("Flavor: {}, Calories: {}", self.flavor, self.calories)
+        format!("Flavor: {}, Calories: {} ({})", self.flavor, self.calories, self.nutrition())
+    }
+    fn sku(&self) -> Sku {
+        self.sku.clone()
+    }
+    fn name(&self) -> String {
+        format!("{} Soda", self.flavor)
+    }
+    fn price(&self) -> Money {
+        self.price
+    }
+    fn nutrition(&self) -> Nutrition {
+        let calories = self.calories as f64;
+        let caffeine = nutrition::SODA_CAFFEINE_PER_OUNCE * self.glass.capacity();
+        Nutrition::new(calories, calories / nutrition::CALORIES_PER_GRAM_OF_SUGAR, caffeine, 0.0, 0.0)
+    }
+    fn recipe(&self) -> Recipe {
+        vec![(Ingredient::SodaCanister(self.flavor.clone()), self.glass.capacity())]
+    }
+    fn glass(&self) -> &Glass {
+        &self.glass
+    }
+    fn glass_mut(&mut self) -> &mut Glass {
+        &mut self.glass
     }
 }
 
@@ -71,7 +188,7 @@ impl Display for Soda {
 struct Coffee<T> {
     kind: T,
     milk: Milk,
-    ounces: u32,
+    glass: Glass,
 }
 
 impl <T> Coffee<T> {
@@ -79,24 +196,82 @@ impl <T> Coffee<T> {
         Self {
             kind: kind,
             milk: milk,
-            ounces: ounces
+            glass: Glass::new(ounces as f64),
         }
     }
 }
 
 impl<T: Debug> Debug for Coffee<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        return f.debug_struct("Coffee").field("kind", &self.kind).field("milk", &self.milk).field("ounces", &self.ounces).finish();
+        return f.debug_struct("Coffee").field("kind", &self.kind).field("milk", &self.milk).field("ounces", &self.glass.remaining()).field("capacity", &self.glass.capacity()).finish();
 
     }
 }
 
 impl<T: Display> Drinkable for Coffee<T> {
-    fn consume(&mut self) {
-        self.ounces = 0;
-    }
     fn get_data(&self) -> String {
-        format!("A delicious {} ounce {}", self.ounces, self.kind)
+        format!("A delicious {} ounce {} ({})", self.glass.remaining(), self.kind, self.nutrition())
+    }
+    fn sku(&self) -> Sku {
+        let size = format!("{}OZ", self.glass.capacity());
+        Sku::from_parts(&["COFFEE", &self.kind.to_string(), &format!("{:?}", self.milk), &size])
+    }
+    fn name(&self) -> String {
+        self.kind.to_string()
+    }
+    fn price(&self) -> Money {
+        COFFEE_PRICE_PER_OUNCE * self.glass.capacity().round() as u32
+    }
+    fn nutrition(&self) -> Nutrition {
+        self.nutrition_with_milk(self.milk)
+    }
+    fn is_espresso_based(&self) -> bool {
+        true
+    }
+    fn nutrition_with_milk(&self, milk: Milk) -> Nutrition {
+        let milk_ounces = self.glass.capacity() * MILK_SHARE_OF_COFFEE;
+        let coffee_ounces = self.glass.capacity() - milk_ounces;
+        nutrition::COFFEE_BASE_PER_OUNCE * coffee_ounces + milk.nutrition_per_ounce() * milk_ounces
+    }
+    fn recipe(&self) -> Recipe {
+        let milk_ounces = self.glass.capacity() * MILK_SHARE_OF_COFFEE;
+        let coffee_ounces = self.glass.capacity() - milk_ounces;
+        vec![
+            (Ingredient::Beans, coffee_ounces * inventory::BEANS_GRAMS_PER_OUNCE),
+            (Ingredient::Milk(self.milk), milk_ounces),
+        ]
+    }
+    fn glass(&self) -> &Glass {
+        &self.glass
+    }
+    fn glass_mut(&mut self) -> &mut Glass {
+        &mut self.glass
+    }
+}
+
+impl<T: Display> PartialEq for Coffee<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.sku() == other.sku()
+    }
+}
+
+impl<T: Display> Eq for Coffee<T> {}
+
+impl<T: Display> Hash for Coffee<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sku().hash(state);
+    }
+}
+
+impl<T: Display> PartialOrd for Coffee<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Display> Ord for Coffee<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        sku::by_name(self, other)
     }
 }
 
@@ -109,7 +284,7 @@ fn main() {
     let cappuccino = Coffee::new("Arabica", Milk::Oat, 20);
     println!("{}", cappuccino.get_data());
 
-    let pepsi = Soda::new(160, 3.00, "Cherry".to_string());
+    let pepsi = Soda::new(160, Money::from_dollars(3, 0), "Cherry".to_string());
     println!("{pepsi}");
 
     let mut coke = pepsi.clone();
@@ -119,4 +294,122 @@ fn main() {
 
     println!("{:?}", coke);
 
+
+    println!("Café Orders");
+    let mut menu = Menu::new(Pricing::default());
+    menu.add(Coffee::new("Latte", Milk::Whole, 12));
+    menu.add(Coffee::new("Flat White", Milk::Oat, 8));
+    menu.add(Soda::new(150, Money::from_dollars(2, 50), "Lime".to_string()));
+    menu.sort_by_name();
+    print!("{}", menu);
+
+    let mut order = menu.order();
+    order.add("Latte", Customization::new(Size::Large).with_milk(Milk::Oat).with_extra_shots(1).with_flavor("Vanilla"), 1).unwrap();
+    order.add("flat white", Customization::new(Size::Small), 2).unwrap();
+    order.add("Lime Soda", Customization::new(Size::Medium).with_flavor("Cherry"), 1).unwrap();
+    if let Err(error) = order.add("Lime Soda", Customization::new(Size::Small).with_milk(Milk::Almond), 1) {
+        println!("{}", error);
+    }
+    if let Err(error) = order.add("Mocha", Customization::new(Size::Small), 1) {
+        println!("{}", error);
+    }
+    println!("{}", order.ticket());
+
+    println!("Sips and Refills");
+    let mut mocha = Coffee::new("Mocha", Milk::Whole, 16);
+    println!("First sip: {:.1} calories", mocha.sip(4.0).unwrap());
+    println!("Second sip: {:.1} calories", mocha.sip(6.0).unwrap());
+    if let Err(error) = mocha.sip(10.0) { // Only 6 ounces are left
+        println!("{}", error);
+    }
+    if let Err(error) = mocha.refill(12.0) {
+        println!("{}", error);
+    }
+    mocha.top_up();
+    mocha.consume();
+    println!("{:.1} ounces left, {:.1} calories consumed", mocha.remaining(), mocha.calories_consumed());
+    println!("History: {:?}", mocha.glass().history());
+    println!("{:?}", mocha);
+
+    println!("Nutrition Facts");
+    for milk in [Milk::Whole, Milk::Oat, Milk::Almond] {
+        let flat_white = Coffee::new("Flat White", milk, 8);
+        println!("{:?}: {}", milk, flat_white.nutrition());
+    }
+    let mut cola = Soda::new(140, Money::from_dollars(2, 0), "Cola".to_string());
+    cola.stats();
+
+    let mut tracker = CaffeineTracker::default();
+    let mut cold_brew = Coffee::new("Cold Brew", Milk::Almond, 24);
+    println!("Cold brew sip: {}", tracker.sip(&mut cold_brew, 20.0).unwrap());
+    println!("Cola sip: {}", tracker.sip(&mut cola, 6.0).unwrap());
+    cold_brew.top_up();
+    if let Err(error) = tracker.sip(&mut cold_brew, 24.0) { // Another 216mg would blow through the daily limit
+        println!("{}", error);
+    }
+    println!("Caffeine today: {:.0}mg, {:.0}mg left", tracker.total_mg(), tracker.remaining_mg());
+    for (drink, caffeine) in tracker.sips() {
+        println!("  {} --> {:.0}mg", drink, caffeine);
+    }
+    println!("Cold brew consumed so far: {}", cold_brew.nutrition_consumed());
+    tracker.reset(); // The next morning
+    println!("Cold brew sip the next day: {}", tracker.sip(&mut cold_brew, 20.0).unwrap());
+
+    println!("Drink Identity and Ordering");
+    let cherry = Soda::new(160, Money::from_dollars(3, 0), "Cherry".to_string());
+    let lime = Soda::new(150, Money::from_dollars(3, 0), "Lime".to_string());
+    let discounted_cherry = Soda::new(160, Money::from_dollars(2, 25), "Cherry".to_string());
+    println!("Cherry == Lime --> {}", cherry == lime); // Same price, different drinks
+    println!("Cherry vs Lime by price --> {:?}", sku::by_price(&cherry, &lime));
+    println!("Cherry == discounted Cherry --> {}", cherry == discounted_cherry); // Same drink, different price
+
+    let mut stock: HashMap<Soda, u32> = HashMap::new(); // Hash goes by SKU, so both cherries land on one key
+    for soda in [cherry.clone(), lime.clone(), discounted_cherry.clone()] {
+        *stock.entry(soda).or_insert(0) += 1;
+    }
+    println!("Cherry cans in stock: {}", stock[&cherry]);
+
+    let sorted: BTreeSet<Soda> = [lime, cherry, Soda::new(140, Money::from_dollars(2, 0), "Cola".to_string())].into_iter().collect();
+    for soda in &sorted {
+        println!("  {} ({}) {}", soda.name(), soda.sku(), soda.price());
+    }
+    menu.sort_by_price();
+    print!("{}", menu);
+    println!("0.10 + 0.20 == 0.30 in f64 --> {}", 0.10 + 0.20 == 0.30);
+    println!("$0.10 + $0.20 == $0.30 in Money --> {}", Money::from_cents(10) + Money::from_cents(20) == Money::from_cents(30));
+
+    println!("Ingredient Inventory");
+    let mut stock = Inventory::new();
+    stock.restock(Ingredient::Beans, 100.0).unwrap();
+    stock.restock(Ingredient::Milk(Milk::Whole), 32.0).unwrap();
+    stock.restock(Ingredient::Milk(Milk::Oat), 4.0).unwrap();
+    stock.restock(Ingredient::Syrup("Vanilla".to_string()), 10.0).unwrap();
+    stock.restock(Ingredient::SodaCanister("Lime".to_string()), 48.0).unwrap();
+    if let Err(error) = stock.restock(Ingredient::Beans, -100.0) {
+        println!("{}", error);
+    }
+    stock.set_low_threshold(Ingredient::Beans, 50.0);
+    stock.set_low_threshold(Ingredient::Milk(Milk::Oat), 8.0);
+    stock.set_low_threshold(Ingredient::SodaCanister("Lime".to_string()), 24.0);
+
+    let black = stock.prepare(Coffee::new("Black", Milk::Whole, 10)).unwrap();
+    println!("Made {} --> {:.1}g of beans left", black.name(), stock.level(&Ingredient::Beans));
+    if let Err(error) = stock.prepare(Coffee::new("Black", Milk::Almond, 10)) { // No almond milk on the shelf
+        println!("{}", error);
+    }
+
+    let mut order = menu.order();
+    order.add("Latte", Customization::new(Size::Medium).with_flavor("Vanilla"), 1).unwrap();
+    order.add("Lime Soda", Customization::new(Size::Small), 2).unwrap();
+    println!("{}", order.fulfil(&mut stock).unwrap());
+
+    let mut order = menu.order();
+    order.add("Flat White", Customization::new(Size::Large).with_extra_shots(2), 1).unwrap();
+    order.add("Latte", Customization::new(Size::Small).with_milk(Milk::Oat), 1).unwrap();
+    if let Err(error) = order.fulfil(&mut stock) { // The oat milk runs out, so the flat white isn't made either
+        println!("{}", error);
+    }
+    for low in stock.low_stock_report() {
+        println!("Low stock: {} at {:.1}{} (reorder at {:.1}{})", low.ingredient, low.level, low.ingredient.unit(), low.threshold, low.ingredient.unit());
+    }
 }
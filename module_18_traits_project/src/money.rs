@@ -0,0 +1,63 @@
+// Exact money as a whole number of cents. f64 can't represent most cent amounts (0.1 + 0.2 != 0.3), so prices and totals
+// are kept as integers and only turned into dollars for display.
+use std::fmt::{Display, Formatter, Result};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Money {
+    cents: i64,
+}
+
+impl Money {
+    pub const ZERO: Money = Money { cents: 0 };
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Self { cents }
+    }
+
+    pub const fn from_dollars(dollars: i64, cents: i64) -> Self {
+        Self { cents: dollars * 100 + cents }
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    fn add(self, rhs: Self) -> Self::Output {
+        Money { cents: self.cents + rhs.cents }
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Self) {
+        self.cents += rhs.cents;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Money { cents: self.cents - rhs.cents }
+    }
+}
+
+impl Mul<u32> for Money { // Quantity times a unit price
+    type Output = Money;
+    fn mul(self, rhs: u32) -> Self::Output {
+        Money { cents: self.cents * rhs as i64 }
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Self {
+        iter.fold(Money::ZERO, |total, amount| total + amount)
+    }
+}
+
+impl Display for Money { // Uses pad so width and alignment flags like {:>8} still work
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let cents = self.cents.unsigned_abs();
+        f.pad(&format!("{}${}.{:02}", sign, cents / 100, cents % 100))
+    }
+}
@@ -0,0 +1,146 @@
+// Nutrition facts for drinks. Values are per ounce so they scale with the size of the drink and with partial sips.
+use std::fmt::{Display, Formatter, Result};
+use std::ops::{Add, Mul};
+
+use crate::Drinkable;
+use crate::Milk;
+use crate::glass::DrinkError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Nutrition {
+    pub calories: f64,
+    pub sugar_g: f64,
+    pub caffeine_mg: f64,
+    pub fat_g: f64,
+    pub protein_g: f64,
+}
+
+impl Nutrition {
+    pub const fn new(calories: f64, sugar_g: f64, caffeine_mg: f64, fat_g: f64, protein_g: f64) -> Self {
+        Self { calories, sugar_g, caffeine_mg, fat_g, protein_g }
+    }
+}
+
+impl Add for Nutrition {
+    type Output = Nutrition;
+    fn add(self, rhs: Self) -> Self::Output {
+        Nutrition {
+            calories: self.calories + rhs.calories,
+            sugar_g: self.sugar_g + rhs.sugar_g,
+            caffeine_mg: self.caffeine_mg + rhs.caffeine_mg,
+            fat_g: self.fat_g + rhs.fat_g,
+            protein_g: self.protein_g + rhs.protein_g,
+        }
+    }
+}
+
+impl Mul<f64> for Nutrition { // Scaling, e.g. per-ounce values times the number of ounces
+    type Output = Nutrition;
+    fn mul(self, rhs: f64) -> Self::Output {
+        Nutrition {
+            calories: self.calories * rhs,
+            sugar_g: self.sugar_g * rhs,
+            caffeine_mg: self.caffeine_mg * rhs,
+            fat_g: self.fat_g * rhs,
+            protein_g: self.protein_g * rhs,
+        }
+    }
+}
+
+impl Display for Nutrition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{:.0} cal, {:.1}g sugar, {:.0}mg caffeine, {:.1}g fat, {:.1}g protein",
+            self.calories, self.sugar_g, self.caffeine_mg, self.fat_g, self.protein_g
+        )
+    }
+}
+
+// Brewed coffee, before any milk goes in
+pub const COFFEE_BASE_PER_OUNCE: Nutrition = Nutrition::new(0.3, 0.0, 12.0, 0.0, 0.04);
+
+// Most colas have about 34mg of caffeine per 12 ounce can and get all of their calories from sugar (4 calories per gram)
+pub const SODA_CAFFEINE_PER_OUNCE: f64 = 34.0 / 12.0;
+pub const CALORIES_PER_GRAM_OF_SUGAR: f64 = 4.0;
+
+pub const DAILY_CAFFEINE_LIMIT_MG: f64 = 400.0; // The FDA's guidance for healthy adults
+
+impl Milk {
+    pub fn nutrition_per_ounce(&self) -> Nutrition {
+        match self {
+            Milk::Whole => Nutrition::new(18.75, 1.5, 0.0, 1.0, 1.0),
+            Milk::Oat => Nutrition::new(15.0, 0.875, 0.0, 0.625, 0.375),
+            Milk::Almond => Nutrition::new(5.0, 0.0, 0.0, 0.375, 0.125),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaffeineError {
+    Drink(DrinkError),
+    OverLimit { sip_mg: f64, remaining_mg: f64 },
+}
+
+impl Display for CaffeineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            CaffeineError::Drink(error) => write!(f, "{}", error),
+            CaffeineError::OverLimit { sip_mg, remaining_mg } => {
+                write!(f, "That sip has {:.0}mg of caffeine but only {:.0}mg is left for today", sip_mg, remaining_mg)
+            }
+        }
+    }
+}
+
+impl From<DrinkError> for CaffeineError {
+    fn from(error: DrinkError) -> Self {
+        CaffeineError::Drink(error)
+    }
+}
+
+// Keeps a running total of the caffeine from every sip today, across any number of drinks, and refuses a sip that would go over the limit
+#[derive(Debug, Clone)]
+pub struct CaffeineTracker {
+    limit_mg: f64,
+    sips: Vec<(String, f64)>, // Drink name and the caffeine in that sip
+}
+
+impl Default for CaffeineTracker {
+    fn default() -> Self {
+        Self::new(DAILY_CAFFEINE_LIMIT_MG)
+    }
+}
+
+impl CaffeineTracker {
+    pub fn new(limit_mg: f64) -> Self {
+        Self { limit_mg, sips: Vec::new() }
+    }
+
+    pub fn total_mg(&self) -> f64 {
+        self.sips.iter().map(|(_, caffeine)| caffeine).sum()
+    }
+
+    pub fn remaining_mg(&self) -> f64 {
+        (self.limit_mg - self.total_mg()).max(0.0)
+    }
+
+    pub fn sips(&self) -> &[(String, f64)] {
+        &self.sips
+    }
+
+    pub fn sip(&mut self, drink: &mut dyn Drinkable, ounces: f64) -> std::result::Result<Nutrition, CaffeineError> {
+        let sip = drink.nutrition_per_ounce() * ounces;
+        if sip.caffeine_mg > self.remaining_mg() {
+            return Err(CaffeineError::OverLimit { sip_mg: sip.caffeine_mg, remaining_mg: self.remaining_mg() });
+        }
+        drink.sip(ounces)?;
+        self.sips.push((drink.name(), sip.caffeine_mg));
+        Ok(sip)
+    }
+
+    // A new day starts with a clean slate
+    pub fn reset(&mut self) {
+        self.sips.clear();
+    }
+}
@@ -0,0 +1,100 @@
+// Drink identity. Two drinks are the same drink when they have the same SKU (stock keeping unit), regardless of price
+// or how much is left in the glass. Comparing by price is a separate, explicit operation.
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result};
+use std::hash::{Hash, Hasher};
+
+use crate::Drinkable;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sku(String);
+
+impl Sku {
+    // Builds a SKU like "SODA-CHERRY" from its parts, upper-cased with spaces turned into dashes so it's stable across spellings
+    pub fn from_parts(parts: &[&str]) -> Self {
+        let joined = parts.iter().map(|part| normalize(part)).collect::<Vec<_>>().join("-");
+        Self(joined)
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.trim().to_uppercase().replace(' ', "-")
+}
+
+impl Display for Sku {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// Compares only what the drinks cost, so two different drinks can come out Equal here
+pub fn by_price(a: &dyn Drinkable, b: &dyn Drinkable) -> Ordering {
+    a.price().cmp(&b.price())
+}
+
+// Menus sort alphabetically by name, normalized the same way as SKUs so "Cherry" and "cherry" sort together. A SKU is
+// built from the same text as the name, so drinks with the same SKU always have the same normalized name and come out
+// Equal, and the SKU breaks ties between different drinks. That keeps Ord consistent with Eq.
+pub fn by_name(a: &dyn Drinkable, b: &dyn Drinkable) -> Ordering {
+    normalize(&a.name()).cmp(&normalize(&b.name())).then_with(|| a.sku().cmp(&b.sku()))
+}
+
+// Identity for trait objects, so Box<dyn Drinkable> can be compared, sorted and used as a map key
+impl PartialEq for dyn Drinkable {
+    fn eq(&self, other: &Self) -> bool {
+        self.sku() == other.sku()
+    }
+}
+
+impl Eq for dyn Drinkable {}
+
+impl PartialOrd for dyn Drinkable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for dyn Drinkable {
+    fn cmp(&self, other: &Self) -> Ordering {
+        by_name(self, other)
+    }
+}
+
+impl Hash for dyn Drinkable {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sku().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::Soda;
+    use crate::money::Money;
+
+    fn soda(flavor: &str) -> Soda {
+        Soda::new(150, Money::from_dollars(2, 0), flavor.to_string())
+    }
+
+    #[test]
+    fn same_sku_is_equal_under_eq_and_ord() {
+        let (upper, lower) = (soda("Cherry"), soda("cherry"));
+        assert!(upper == lower);
+        assert_eq!(upper.cmp(&lower), Ordering::Equal);
+        assert_eq!(BTreeSet::from([upper, lower]).len(), 1);
+    }
+
+    #[test]
+    fn ordering_ignores_case_and_stays_transitive() {
+        let mut sodas = [soda("cherry"), soda("Cola"), soda("Cherry"), soda("apple")];
+        sodas.sort();
+        let names: Vec<String> = sodas.iter().map(|drink| drink.name()).collect();
+        assert_eq!(names[0], "apple Soda");
+        assert_eq!(names[3], "Cola Soda");
+        for window in sodas.windows(2) {
+            assert_ne!(window[0].cmp(&window[1]), Ordering::Greater);
+        }
+    }
+}